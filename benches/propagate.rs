@@ -1,4 +1,4 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
 #[path = "../test_cases.rs"]
 mod test_cases;
 use test_cases::*;
@@ -30,5 +30,45 @@ pub fn criterion_benchmark(criterion: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, criterion_benchmark);
+// one representative TLE per orbit type, from the verification test cases
+// the near earth, deep space, deep space half-day resonant and deep space geosynchronous resonant paths are covered
+const ORBIT_TYPES: [(&str, &str, &str); 4] = [
+    (
+        "leo",
+        "1 06251U 62025E   06176.82412014  .00008885  00000-0  12808-3 0  3985",
+        "2 06251  58.0579  54.0425 0030035 139.1568 221.1854 15.56387291  6774",
+    ),
+    (
+        "meo",
+        "1 28129U 03058A   06175.57071136 -.00000104  00000-0  10000-3 0   459",
+        "2 28129  54.7298 324.8098 0048506 266.2640  93.1663  2.00562768 18443",
+    ),
+    (
+        "geo",
+        "1 28626U 05008A   06176.46683397 -.00000205  00000-0  10000-3 0  2190",
+        "2 28626   0.0019 286.9433 0000335  13.7918  55.6504  1.00270176  4891",
+    ),
+    (
+        "molniya",
+        "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813",
+        "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656",
+    ),
+];
+
+pub fn orbit_types_benchmark(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("orbit types");
+    for (name, line1, line2) in ORBIT_TYPES.iter() {
+        let elements = sgp4::Elements::from_tle(None, line1.as_bytes(), line2.as_bytes()).unwrap();
+        group.bench_function(format!("new {}", name), |b| {
+            b.iter(|| sgp4::Constants::from_elements(black_box(&elements)).unwrap())
+        });
+        let constants = sgp4::Constants::from_elements(&elements).unwrap();
+        group.bench_function(format!("propagate {}", name), |b| {
+            b.iter(|| constants.propagate(black_box(1440.0)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark, orbit_types_benchmark);
 criterion_main!(benches);
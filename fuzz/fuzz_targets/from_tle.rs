@@ -0,0 +1,13 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+// parsing must return an error, never panic, whatever the input
+fuzz_target!(|data: &[u8]| {
+    if let Some(separator) = data.iter().position(|character| *character == b'\n') {
+        let _ = sgp4::Elements::from_tle(None, &data[..separator], &data[separator + 1..]);
+    }
+    if let Ok(tles) = std::str::from_utf8(data) {
+        let _ = sgp4::parse_2les(tles);
+        let _ = sgp4::parse_3les(tles);
+    }
+});
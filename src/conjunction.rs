@@ -0,0 +1,220 @@
+use crate::fit;
+use crate::gp;
+use crate::model;
+use crate::propagator;
+
+// margin added to the apogee / perigee filter to account for short-period and drag variations in km
+const APSIS_FILTER_MARGIN: f64 = 50.0;
+
+// sampling step of the fine search in min
+const SAMPLING_STEP: f64 = 1.0;
+
+// convergence tolerance of the closest approach refinement in min
+const TIME_TOLERANCE: f64 = 1.0e-6;
+
+/// A close approach between a primary object and a secondary object
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Conjunction {
+    /// The index of the secondary object in the list given to `screen_conjunctions`
+    pub secondary: usize,
+
+    /// The time of closest approach in minutes since the primary object's epoch
+    pub t: f64,
+
+    /// The miss distance in km
+    pub distance: f64,
+
+    /// The relative speed at closest approach in km.s⁻¹
+    pub relative_speed: f64,
+}
+
+// returns the distance (km) and relative speed (km.s⁻¹) between two objects
+// t is given in minutes since the primary object's epoch
+fn separation(
+    primary: &propagator::Constants,
+    secondary: &propagator::Constants,
+    epoch_offset: f64,
+    t: f64,
+) -> Option<(f64, f64)> {
+    let first = primary.propagate(t).ok()?;
+    let second = secondary.propagate(t + epoch_offset).ok()?;
+    Some((
+        ((first.position[0] - second.position[0]).powi(2)
+            + (first.position[1] - second.position[1]).powi(2)
+            + (first.position[2] - second.position[2]).powi(2))
+        .sqrt(),
+        ((first.velocity[0] - second.velocity[0]).powi(2)
+            + (first.velocity[1] - second.velocity[1]).powi(2)
+            + (first.velocity[2] - second.velocity[2]).powi(2))
+        .sqrt(),
+    ))
+}
+
+/// Finds the close approaches between a primary object and a catalog of secondary objects
+///
+/// The screening has two stages. Pairs whose perigee / apogee shells cannot come closer
+/// than the threshold (with a 50 km margin for short-period and drag variations) are skipped.
+/// The surviving pairs are sampled every minute, and each local minimum of the distance
+/// is refined with a golden-section search. Samples at which either propagation fails are ignored.
+/// An error is returned if the window is not finite or requires more than `MAX_SAMPLES` samples.
+///
+/// # Arguments
+///
+/// * `primary` - The propagator of the object of interest
+/// * `secondaries` - The propagators of the objects screened against the primary
+/// * `window` - The screening window in minutes since the primary object's epoch
+/// * `threshold_km` - The miss distance below which a close approach is reported, in km
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> sgp4::Result<()> {
+/// let primary = sgp4::Constants::from_elements(&sgp4::Elements::from_tle(
+///     Some("ISS (ZARYA)".to_owned()),
+///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+/// )?)?;
+/// let secondaries = sgp4::parse_2les(
+///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992\n\
+///      2 25544  51.6461  41.2784 0001413  89.1723 100.4612 15.49507896236009\n",
+/// )?
+/// .iter()
+/// .map(sgp4::Constants::from_elements)
+/// .collect::<sgp4::Result<Vec<_>>>()?;
+/// for conjunction in sgp4::screen_conjunctions(&primary, &secondaries, 0.0..1440.0, 20.0)? {
+///     println!(
+///         "{:.3} km at t = {:.3} min",
+///         conjunction.distance, conjunction.t
+///     );
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+pub fn screen_conjunctions(
+    primary: &propagator::Constants,
+    secondaries: &[propagator::Constants],
+    window: std::ops::Range<f64>,
+    threshold_km: f64,
+) -> gp::Result<Vec<Conjunction>> {
+    let mut conjunctions = Vec::new();
+    if window.is_empty() {
+        return Ok(conjunctions);
+    }
+    let steps = propagator::step_count(window.end - window.start, SAMPLING_STEP)?;
+    let times = (0..=steps)
+        .map(|step| (window.start + step as f64 * SAMPLING_STEP).min(window.end))
+        .collect::<Vec<f64>>();
+    for (index, secondary) in secondaries.iter().enumerate() {
+        if primary.perigee_radius().max(secondary.perigee_radius())
+            - primary.apogee_radius().min(secondary.apogee_radius())
+            > threshold_km + APSIS_FILTER_MARGIN
+        {
+            continue;
+        }
+
+        // converts minutes since the primary epoch to minutes since the secondary epoch
+        let epoch_offset = (primary.epoch - secondary.epoch) * (365.25 * 24.0 * 60.0);
+        let distances = times
+            .iter()
+            .map(|t| {
+                separation(primary, secondary, epoch_offset, *t)
+                    .map_or(f64::INFINITY, |(distance, _)| distance)
+            })
+            .collect::<Vec<f64>>();
+        for sample in 0..distances.len() {
+            let left = if sample > 0 {
+                distances[sample - 1]
+            } else {
+                f64::INFINITY
+            };
+            let right = distances.get(sample + 1).copied().unwrap_or(f64::INFINITY);
+            if !distances[sample].is_finite()
+                || distances[sample] > left
+                || distances[sample] >= right
+            {
+                continue;
+            }
+
+            // golden-section search of the minimum distance in the bracket
+            let t = fit::minimize(
+                |t| {
+                    separation(primary, secondary, epoch_offset, t)
+                        .map_or(f64::INFINITY, |(distance, _)| distance)
+                },
+                times[sample.saturating_sub(1)],
+                times[(sample + 1).min(times.len() - 1)],
+                TIME_TOLERANCE,
+            );
+            if let Some((distance, relative_speed)) =
+                separation(primary, secondary, epoch_offset, t)
+            {
+                if distance < threshold_km {
+                    conjunctions.push(Conjunction {
+                        secondary: index,
+                        t: t,
+                        distance: distance,
+                        relative_speed: relative_speed,
+                    });
+                }
+            }
+        }
+    }
+    conjunctions.sort_by(|first, second| {
+        first
+            .t
+            .partial_cmp(&second.t)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    Ok(conjunctions)
+}
+
+/// Returns the position difference between two propagators in the radial, in-track, cross-track frame
+///
+/// Both propagators are evaluated at the same instant, and the difference b - a is projected
+/// on the RIC frame of the first prediction: R is along the position, C is along the
+/// angular momentum r × v, and I = C × R completes the right-handed frame (along the velocity
+/// for circular orbits). This is typically used to compare an older element set (b) to a fresher one (a)
+/// of the same satellite.
+///
+/// # Arguments
+///
+/// * `a` - The reference propagator, which defines the RIC frame
+/// * `b` - The compared propagator
+/// * `t` - The number of minutes since the reference propagator's epoch
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> sgp4::Result<()> {
+/// let constants = sgp4::Constants::from_elements(&sgp4::Elements::from_tle(
+///     Some("ISS (ZARYA)".to_owned()),
+///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+/// )?)?;
+///
+/// // the drag term mostly affects the in-track position
+/// let [radial, in_track, cross_track] =
+///     sgp4::compare_tles(&constants, &constants.without_drag()?, 1440.0)?;
+/// assert!(in_track.abs() > radial.abs() && in_track.abs() > cross_track.abs());
+/// #     Ok(())
+/// # }
+/// ```
+pub fn compare_tles(
+    a: &propagator::Constants,
+    b: &propagator::Constants,
+    t: f64,
+) -> gp::Result<[f64; 3]> {
+    let first = a.propagate(t)?;
+    let second = b.propagate(t + (a.epoch - b.epoch) * (365.25 * 24.0 * 60.0))?;
+    let difference = [
+        second.position[0] - first.position[0],
+        second.position[1] - first.position[1],
+        second.position[2] - first.position[2],
+    ];
+    let [radial, in_track, cross_track] = first.ric_basis();
+    Ok([
+        model::dot(&radial, &difference),
+        model::dot(&in_track, &difference),
+        model::dot(&cross_track, &difference),
+    ])
+}
@@ -5,7 +5,7 @@ use crate::third_body;
 use std::cmp::Ordering;
 
 // θ̇ = 4.37526908801129966 × 10⁻³ rad.min⁻¹
-const SIDEREAL_SPEED: f64 = 4.37526908801129966e-3;
+pub(crate) const SIDEREAL_SPEED: f64 = 4.37526908801129966e-3;
 
 // eₛ = 0.01675
 const SOLAR_ECCENTRICITY: f64 = 0.01675;
@@ -28,6 +28,9 @@ const LUNAR_PERTURBATION_COEFFICIENT: f64 = 4.7968065e-7;
 // |Δt| = 720 min
 const DELTA_T: f64 = 720.0;
 
+// |Δt| = 60 min (high precision integrator)
+const HIGH_PRECISION_DELTA_T: f64 = 60.0;
+
 // λ₃₁ = 0.13130908
 const LAMBDA31: f64 = 0.13130908;
 
@@ -60,6 +63,7 @@ pub struct ResonanceState {
     t: f64,
     mean_motion: f64,
     lambda: f64,
+    high_precision: bool,
 }
 
 impl ResonanceState {
@@ -68,17 +72,182 @@ impl ResonanceState {
             t: 0.0,
             mean_motion: mean_motion_0,
             lambda: lambda_0,
+            high_precision: false,
         }
     }
 
     /// Returns the integrator's time in minutes since epoch
     ///
     /// The integrator time changes monotonically in Δt = 720 min increments
-    /// or Δt = -720 min decrements, depending on the propagation time sign.
+    /// or Δt = -720 min decrements, depending on the propagation time sign
+    /// (60 min with the high precision integrator).
     pub fn t(&self) -> f64 {
         self.t
     }
 
+    /// Returns the integrator's time in minutes since epoch
+    ///
+    /// This is an alias of `ResonanceState::t`.
+    pub fn time(&self) -> f64 {
+        self.t
+    }
+
+    /// Returns the integrator's resonance mean motion nᵢ in rad.min⁻¹
+    ///
+    /// nᵢ is the secular mean motion including the resonance effects, at the integrator's time.
+    pub fn mean_motion(&self) -> f64 {
+        self.mean_motion
+    }
+
+    /// Returns the integrator's resonance angle λᵢ in rad
+    ///
+    /// λᵢ is the secular resonance angle at the integrator's time.
+    /// It is not reduced to the range [0, 2π[.
+    pub fn lambda(&self) -> f64 {
+        self.lambda
+    }
+
+    /// Selects the high precision resonance integrator
+    ///
+    /// The standard SGP4 integrator uses Δt = 720 min steps with a second-order Taylor update,
+    /// which accumulates error over long deep space propagations.
+    /// The high precision integrator uses a fourth-order Runge-Kutta scheme with Δt = 60 min steps,
+    /// including for the final partial step. It is slower and its predictions do not match
+    /// other SGP4 implementations, but they are closer to the exact solution of the resonance equations.
+    ///
+    /// The state should be reset with `Constants::initial_state` before changing integrators.
+    ///
+    /// # Arguments
+    ///
+    /// * `high_precision` - Whether to use the Runge-Kutta integrator
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         None,
+    ///         "1 28626U 05008A   06176.46683397 -.00000205  00000-0  10000-3 0  2190".as_bytes(),
+    ///         "2 28626   0.0019 286.9433 0000335  13.7918  55.6504  1.00270176  4891".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let mut state = constants.initial_state();
+    /// if let Some(state) = state.as_mut() {
+    ///     state.set_high_precision(true);
+    /// }
+    /// let prediction = constants.propagate_from_state(60.0 * 24.0 * 30.0, state.as_mut(), false)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn set_high_precision(&mut self, high_precision: bool) {
+        self.high_precision = high_precision;
+    }
+
+    // returns ṅᵢ and ∂ṅᵢ / ∂λᵢ at the time tᵢ
+    fn mean_motion_dot(
+        resonance: &propagator::Resonance,
+        argument_of_perigee_0: f64,
+        lambda: f64,
+        t: f64,
+    ) -> (f64, f64) {
+        match resonance {
+            propagator::Resonance::OneDay { dr1, dr2, dr3 } => (
+                // ṅᵢ = 𝛿ᵣ₁ sin(λᵢ - λ₃₁) + 𝛿ᵣ₂ sin(2 (λᵢ - λ₂₂)) + 𝛿ᵣ₃ sin(3 (λᵢ - λ₃₃))
+                dr1 * (lambda - LAMBDA31).sin()
+                    + dr2 * (2.0 * (lambda - LAMBDA22)).sin()
+                    + dr3 * (3.0 * (lambda - LAMBDA33)).sin(),
+                // ∂ṅᵢ / ∂λᵢ = 𝛿ᵣ₁ cos(λᵢ - λ₃₁) + 𝛿ᵣ₂ cos(2 (λᵢ - λ₂₂)) + 𝛿ᵣ₃ cos(3 (λᵢ - λ₃₃))
+                dr1 * (lambda - LAMBDA31).cos()
+                    + 2.0 * dr2 * (2.0 * (lambda - LAMBDA22)).cos()
+                    + 3.0 * dr3 * (3.0 * (lambda - LAMBDA33)).cos(),
+            ),
+            propagator::Resonance::HalfDay {
+                d2201,
+                d2211,
+                d3210,
+                d3222,
+                d4410,
+                d4422,
+                d5220,
+                d5232,
+                d5421,
+                d5433,
+                k14,
+            } => {
+                // ωᵢ = ω₀ + ω̇ tᵢ
+                let argument_of_perigee_i = argument_of_perigee_0 + k14 * t;
+                (
+                    // ṅᵢ = Σ₍ₗₘₚₖ₎ Dₗₘₚₖ sin((l - 2 p) ωᵢ + m / 2 λᵢ - Gₗₘ)
+                    // (l, m, p, k) ∈ {(2, 2, 0, -1), (2, 2, 1, 1), (3, 2, 1, 0),
+                    //     (3, 2, 2, 2), (4, 4, 1, 0), (4, 4, 2, 2), (5, 2, 2, 0),
+                    //     (5, 2, 3, 2), (5, 4, 2, 1), (5, 4, 3, 3)}
+                    d2201 * (2.0 * argument_of_perigee_i + lambda - G22).sin()
+                        + d2211 * (lambda - G22).sin()
+                        + d3210 * (argument_of_perigee_i + lambda - G32).sin()
+                        + d3222 * (-argument_of_perigee_i + lambda - G32).sin()
+                        + d4410 * (2.0 * argument_of_perigee_i + 2.0 * lambda - G44).sin()
+                        + d4422 * (2.0 * lambda - G44).sin()
+                        + d5220 * (argument_of_perigee_i + lambda - G52).sin()
+                        + d5232 * (-argument_of_perigee_i + lambda - G52).sin()
+                        + d5421 * (argument_of_perigee_i + 2.0 * lambda - G54).sin()
+                        + d5433 * (-argument_of_perigee_i + 2.0 * lambda - G54).sin(),
+                    // ∂ṅᵢ / ∂λᵢ = Σ₍ₗₘₚₖ₎ m / 2 Dₗₘₚₖ cos((l - 2 p) ωᵢ + m / 2 λᵢ - Gₗₘ)
+                    // (l, m, p, k) ∈ {(2, 2, 0, -1), (2, 2, 1, 1), (3, 2, 1, 0),
+                    //     (3, 2, 2, 2), (4, 4, 1, 0), (4, 4, 2, 2), (5, 2, 2, 0),
+                    //     (5, 2, 3, 2), (5, 4, 2, 1), (5, 4, 3, 3)}
+                    d2201 * (2.0 * argument_of_perigee_i + lambda - G22).cos()
+                        + d2211 * (lambda - G22).cos()
+                        + d3210 * (argument_of_perigee_i + lambda - G32).cos()
+                        + d3222 * (-argument_of_perigee_i + lambda - G32).cos()
+                        + d5220 * (argument_of_perigee_i + lambda - G52).cos()
+                        + d5232 * (-argument_of_perigee_i + lambda - G52).cos()
+                        + 2.0
+                            * (d4410 * (2.0 * argument_of_perigee_i + 2.0 * lambda - G44).cos()
+                                + d4422 * (2.0 * lambda - G44).cos()
+                                + d5421 * (argument_of_perigee_i + 2.0 * lambda - G54).cos()
+                                + d5433 * (-argument_of_perigee_i + 2.0 * lambda - G54).cos()),
+                )
+            }
+        }
+    }
+
+    // advances (tᵢ, nᵢ, λᵢ) by h with a fourth-order Runge-Kutta step
+    // the resonance equations are λ̇ = n + λ̇₀ and ṅ = ṅ(λ, t)
+    fn runge_kutta_step(
+        &mut self,
+        argument_of_perigee_0: f64,
+        lambda_dot_0: f64,
+        resonance: &propagator::Resonance,
+        h: f64,
+    ) {
+        let derivatives = |t: f64, mean_motion: f64, lambda: f64| {
+            (
+                ResonanceState::mean_motion_dot(resonance, argument_of_perigee_0, lambda, t).0,
+                mean_motion + lambda_dot_0,
+            )
+        };
+        let (k1_n, k1_l) = derivatives(self.t, self.mean_motion, self.lambda);
+        let (k2_n, k2_l) = derivatives(
+            self.t + h / 2.0,
+            self.mean_motion + k1_n * h / 2.0,
+            self.lambda + k1_l * h / 2.0,
+        );
+        let (k3_n, k3_l) = derivatives(
+            self.t + h / 2.0,
+            self.mean_motion + k2_n * h / 2.0,
+            self.lambda + k2_l * h / 2.0,
+        );
+        let (k4_n, k4_l) = derivatives(
+            self.t + h,
+            self.mean_motion + k3_n * h,
+            self.lambda + k3_l * h,
+        );
+        self.t += h;
+        self.mean_motion += h / 6.0 * (k1_n + 2.0 * k2_n + 2.0 * k3_n + k4_n);
+        self.lambda += h / 6.0 * (k1_l + 2.0 * k2_l + 2.0 * k3_l + k4_l);
+    }
+
     fn integrate(
         &mut self,
         geopotential: &model::Geopotential,
@@ -98,133 +267,119 @@ impl ResonanceState {
         // θ = θ₀ + 4.37526908801129966 × 10⁻³ t rem 2π
         let sidereal_time =
             (sidereal_time_0 + t * 4.37526908801129966e-3) % (2.0 * std::f64::consts::PI);
+        let step = if self.high_precision {
+            HIGH_PRECISION_DELTA_T
+        } else {
+            DELTA_T
+        };
         let (delta_t, ordering) = if t > 0.0 {
-            (DELTA_T, Ordering::Less)
+            (step, Ordering::Less)
         } else {
-            (-DELTA_T, Ordering::Greater)
+            (-step, Ordering::Greater)
         };
-        loop {
-            // λ̇ᵢ = nᵢ + λ̇₀
-            let lambda_dot = self.mean_motion + lambda_dot_0;
-            let (ni_dot, ni_ddot) = match resonance {
-                propagator::Resonance::OneDay { dr1, dr2, dr3 } => (
-                    // ṅᵢ = 𝛿ᵣ₁ sin(λᵢ - λ₃₁) + 𝛿ᵣ₂ sin(2 (λᵢ - λ₂₂)) + 𝛿ᵣ₃ sin(3 (λᵢ - λ₃₃))
-                    dr1 * (self.lambda - LAMBDA31).sin()
-                        + dr2 * (2.0 * (self.lambda - LAMBDA22)).sin()
-                        + dr3 * (3.0 * (self.lambda - LAMBDA33)).sin(),
-                    // n̈ᵢ = (𝛿ᵣ₁ cos(λᵢ - λ₃₁) + 𝛿ᵣ₂ cos(2 (λᵢ - λ₂₂)) + 𝛿ᵣ₃ cos(3 (λᵢ - λ₃₃))) λ̇ᵢ
-                    (dr1 * (self.lambda - LAMBDA31).cos()
-                        + 2.0 * dr2 * (2.0 * (self.lambda - LAMBDA22)).cos()
-                        + 3.0 * dr3 * (3.0 * (self.lambda - LAMBDA33)).cos())
-                        * lambda_dot,
-                ),
-                propagator::Resonance::HalfDay {
-                    d2201,
-                    d2211,
-                    d3210,
-                    d3222,
-                    d4410,
-                    d4422,
-                    d5220,
-                    d5232,
-                    d5421,
-                    d5433,
-                    k14,
-                } => {
-                    // ωᵢ = ω₀ + ω̇ tᵢ
-                    let argument_of_perigee_i = argument_of_perigee_0 + k14 * self.t;
-                    (
-                        // ṅᵢ = Σ₍ₗₘₚₖ₎ Dₗₘₚₖ sin((l - 2 p) ωᵢ + m / 2 λᵢ - Gₗₘ)
-                        // (l, m, p, k) ∈ {(2, 2, 0, -1), (2, 2, 1, 1), (3, 2, 1, 0),
-                        //     (3, 2, 2, 2), (4, 4, 1, 0), (4, 4, 2, 2), (5, 2, 2, 0),
-                        //     (5, 2, 3, 2), (5, 4, 2, 1), (5, 4, 3, 3)}
-                        d2201 * (2.0 * argument_of_perigee_i + self.lambda - G22).sin()
-                            + d2211 * (self.lambda - G22).sin()
-                            + d3210 * (argument_of_perigee_i + self.lambda - G32).sin()
-                            + d3222 * (-argument_of_perigee_i + self.lambda - G32).sin()
-                            + d4410 * (2.0 * argument_of_perigee_i + 2.0 * self.lambda - G44).sin()
-                            + d4422 * (2.0 * self.lambda - G44).sin()
-                            + d5220 * (argument_of_perigee_i + self.lambda - G52).sin()
-                            + d5232 * (-argument_of_perigee_i + self.lambda - G52).sin()
-                            + d5421 * (argument_of_perigee_i + 2.0 * self.lambda - G54).sin()
-                            + d5433 * (-argument_of_perigee_i + 2.0 * self.lambda - G54).sin(),
-                        // n̈ᵢ = (Σ₍ₗₘₚₖ₎ m / 2 Dₗₘₚₖ cos((l - 2 p) ωᵢ + m / 2 λᵢ - Gₗₘ)) λ̇ᵢ
-                        // (l, m, p, k) ∈ {(2, 2, 0, -1), (2, 2, 1, 1), (3, 2, 1, 0),
-                        //     (3, 2, 2, 2), (4, 4, 1, 0), (4, 4, 2, 2), (5, 2, 2, 0),
-                        //     (5, 2, 3, 2), (5, 4, 2, 1), (5, 4, 3, 3)}
-                        (d2201 * (2.0 * argument_of_perigee_i + self.lambda - G22).cos()
-                            + d2211 * (self.lambda - G22).cos()
-                            + d3210 * (argument_of_perigee_i + self.lambda - G32).cos()
-                            + d3222 * (-argument_of_perigee_i + self.lambda - G32).cos()
-                            + d5220 * (argument_of_perigee_i + self.lambda - G52).cos()
-                            + d5232 * (-argument_of_perigee_i + self.lambda - G52).cos()
-                            + 2.0
-                                * (d4410
-                                    * (2.0 * argument_of_perigee_i + 2.0 * self.lambda - G44)
-                                        .cos()
-                                    + d4422 * (2.0 * self.lambda - G44).cos()
-                                    + d5421
-                                        * (argument_of_perigee_i + 2.0 * self.lambda - G54).cos()
-                                    + d5433
-                                        * (-argument_of_perigee_i + 2.0 * self.lambda - G54)
-                                            .cos()))
-                            * lambda_dot,
-                    )
-                }
-            };
+        let (mean_motion, lambda) = loop {
             if (t - delta_t)
                 .partial_cmp(&self.t)
                 .unwrap_or(Ordering::Equal)
                 == ordering
             {
-                return (
-                    // p₂₈ = (kₑ / (nᵢ + ṅᵢ (t - tᵢ) + ¹/₂ n̈ᵢ (t - tᵢ)²))²ᐟ³
-                    (geopotential.ke
-                        / (self.mean_motion
-                            + ni_dot * (t - self.t)
-                            + ni_ddot * (t - self.t).powi(2) * 0.5))
-                        .powf(2.0 / 3.0),
-                    match resonance {
-                        propagator::Resonance::OneDay { .. } => {
-                            // p₂₉ = λᵢ + λ̇ᵢ (t - tᵢ) + ¹/₂ ṅᵢ (t - tᵢ)² - p₂₂ - p₂₃ + θ
-                            self.lambda
-                                + lambda_dot * (t - self.t)
-                                + ni_dot * (t - self.t).powi(2) * 0.5
-                                - p22
-                                - p23
-                                + sidereal_time
-                        }
-                        propagator::Resonance::HalfDay { .. } => {
-                            // p₂₉ = λᵢ + λ̇ᵢ (t - tᵢ) + ¹/₂ ṅᵢ (t - tᵢ)² - 2 p₂₂ + 2 θ
-                            self.lambda
-                                + lambda_dot * (t - self.t)
-                                + ni_dot * (t - self.t).powi(2) * 0.5
-                                - 2.0 * p22
-                                + 2.0 * sidereal_time
-                        }
-                    },
+                if self.high_precision {
+                    let mut state = *self;
+                    state.runge_kutta_step(
+                        argument_of_perigee_0,
+                        lambda_dot_0,
+                        resonance,
+                        t - self.t,
+                    );
+                    break (state.mean_motion, state.lambda);
+                }
+
+                // λ̇ᵢ = nᵢ + λ̇₀
+                let lambda_dot = self.mean_motion + lambda_dot_0;
+                let (ni_dot, ni_dot_lambda) = ResonanceState::mean_motion_dot(
+                    resonance,
+                    argument_of_perigee_0,
+                    self.lambda,
+                    self.t,
+                );
+
+                // n̈ᵢ = ∂ṅᵢ / ∂λᵢ λ̇ᵢ
+                let ni_ddot = ni_dot_lambda * lambda_dot;
+                break (
+                    // nᵢ + ṅᵢ (t - tᵢ) + ¹/₂ n̈ᵢ (t - tᵢ)²
+                    self.mean_motion + ni_dot * (t - self.t) + ni_ddot * (t - self.t).powi(2) * 0.5,
+                    // λᵢ + λ̇ᵢ (t - tᵢ) + ¹/₂ ṅᵢ (t - tᵢ)²
+                    self.lambda + lambda_dot * (t - self.t) + ni_dot * (t - self.t).powi(2) * 0.5,
                 );
             }
+            if self.high_precision {
+                self.runge_kutta_step(argument_of_perigee_0, lambda_dot_0, resonance, delta_t);
+                continue;
+            }
+
+            // λ̇ᵢ = nᵢ + λ̇₀
+            let lambda_dot = self.mean_motion + lambda_dot_0;
+            let (ni_dot, ni_dot_lambda) = ResonanceState::mean_motion_dot(
+                resonance,
+                argument_of_perigee_0,
+                self.lambda,
+                self.t,
+            );
+
+            // n̈ᵢ = ∂ṅᵢ / ∂λᵢ λ̇ᵢ
+            let ni_ddot = ni_dot_lambda * lambda_dot;
 
             // tᵢ₊₁ = tᵢ + Δt
             self.t += delta_t;
 
-            // nᵢ₊₁ = nᵢ + ṅᵢ Δt + n̈ᵢ (Δt² / 2)
+            // nᵢ₊₁ = nᵢ + ṅᵢ Δt + n̈ᵢ (Δt² / 2)
             self.mean_motion += ni_dot * delta_t + ni_ddot * (DELTA_T.powi(2) / 2.0);
 
-            // λᵢ₊₁ = λᵢ + λ̇ᵢ Δt + ṅᵢ (Δt² / 2)
+            // λᵢ₊₁ = λᵢ + λ̇ᵢ Δt + ṅᵢ (Δt² / 2)
             self.lambda += lambda_dot * delta_t + ni_dot * (DELTA_T.powi(2) / 2.0);
-        }
+        };
+        (
+            // p₂₈ = (kₑ / n)²ᐟ³
+            (geopotential.ke / mean_motion).powf(2.0 / 3.0),
+            match resonance {
+                propagator::Resonance::OneDay { .. } => {
+                    // p₂₉ = λ - p₂₂ - p₂₃ + θ
+                    lambda - p22 - p23 + sidereal_time
+                }
+                propagator::Resonance::HalfDay { .. } => {
+                    // p₂₉ = λ - 2 p₂₂ + 2 θ
+                    lambda - 2.0 * p22 + 2.0 * sidereal_time
+                }
+            },
+        )
     }
 }
 
+// returns the solar and lunar long-period periodic effects (δe, δI, δM, p₄, p₅)
+pub(crate) fn long_period_periodic_effects(
+    solar_perturbations: &third_body::Perturbations,
+    lunar_perturbations: &third_body::Perturbations,
+    t: f64,
+) -> ([f64; 5], [f64; 5]) {
+    let solar =
+        solar_perturbations.long_period_periodic_effects(SOLAR_ECCENTRICITY, SOLAR_MEAN_MOTION, t);
+    let lunar =
+        lunar_perturbations.long_period_periodic_effects(LUNAR_ECCENTRICITY, LUNAR_MEAN_MOTION, t);
+    (
+        [solar.0, solar.1, solar.2, solar.3, solar.4],
+        [lunar.0, lunar.1, lunar.2, lunar.3, lunar.4],
+    )
+}
+
 pub(crate) fn constants<'a>(
     geopotential: &'a model::Geopotential,
     epoch_to_sidereal_time: impl Fn(f64) -> f64,
     epoch: f64,
+    drag_term: f64,
     orbit_0: propagator::Orbit,
     p1: f64,
     a0: f64,
+    atmospheric_s: Option<f64>,
     c1: f64,
     b0: f64,
     c4: f64,
@@ -316,6 +471,9 @@ pub(crate) fn constants<'a>(
     );
     propagator::Constants {
         geopotential: geopotential,
+        epoch: epoch,
+        sidereal_time_0: epoch_to_sidereal_time(epoch),
+        drag_term: drag_term,
 
         // Ω̇ = p₁₄ + (Ω̇ₛ + Ω̇ₗ)
         right_ascension_dot: p14 + (solar_dots.right_ascension + lunar_dots.right_ascension),
@@ -330,6 +488,7 @@ pub(crate) fn constants<'a>(
         c4: c4,
         k0: k0,
         k1: k1,
+        atmospheric_s: atmospheric_s,
         method: propagator::Method::DeepSpace {
             eccentricity_dot: solar_dots.eccentricity + lunar_dots.eccentricity,
             inclination_dot: solar_dots.inclination + lunar_dots.inclination,
@@ -622,6 +781,8 @@ pub(crate) fn constants<'a>(
             },
         },
         orbit_0: orbit_0,
+        kepler_solver: propagator::KeplerSolver::NewtonRaphson,
+        short_period_corrections: true,
     }
 }
 
@@ -638,6 +799,7 @@ impl<'a> propagator::Constants<'a> {
         p22: f64,
         p23: f64,
         afspc_compatibility_mode: bool,
+        warnings: Option<&mut Vec<propagator::Warning>>,
     ) -> gp::Result<(propagator::Orbit, f64, f64, f64, f64, f64, f64)> {
         let (p28, p29) = match resonant {
             propagator::Resonant::No { a0 } => {
@@ -746,6 +908,12 @@ impl<'a> propagator::Constants<'a> {
         if p31 >= 1.0 || p31 < -0.001 {
             Err(gp::Error::new("diverging eccentricity".to_owned()))
         } else {
+            if p31 < 1.0e-6 {
+                if let Some(warnings) = warnings {
+                    warnings.push(propagator::Warning::ClampedEccentricity { eccentricity: p31 });
+                }
+            }
+
             // e = │ 10⁻⁶ + (δeₛ + δeₗ) if p₃₁ < 10⁻⁶
             //     │ p₃₁ + (δeₛ + δeₗ)  otherwise
             let eccentricity =
@@ -807,3 +975,181 @@ impl<'a> propagator::Constants<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod test_cases {
+        include!("../test_cases.rs");
+    }
+
+    #[test]
+    fn test_high_precision_integrator() -> gp::Result<()> {
+        let constants = propagator::Constants::from_elements(&gp::Elements::from_tle(
+            None,
+            "1 28626U 05008A   06176.46683397 -.00000205  00000-0  10000-3 0  2190".as_bytes(),
+            "2 28626   0.0019 286.9433 0000335  13.7918  55.6504  1.00270176  4891".as_bytes(),
+        )?)?;
+        let (lambda_dot_0, resonance) = match &constants.method {
+            propagator::Method::DeepSpace {
+                resonant:
+                    propagator::Resonant::Yes {
+                        lambda_dot_0,
+                        resonance,
+                        ..
+                    },
+                ..
+            } => (*lambda_dot_0, resonance),
+            _ => panic!("the propagator must be resonant"),
+        };
+        let t = 60.0 * 24.0 * 30.0;
+
+        // reference solution with 1 min Runge-Kutta steps
+        let reference_0 = constants.initial_state().unwrap();
+        let mut reference = reference_0;
+        for _ in 0..(t as usize) {
+            reference.runge_kutta_step(
+                constants.orbit_0.argument_of_perigee,
+                lambda_dot_0,
+                resonance,
+                1.0,
+            );
+        }
+
+        // independent check of the reference solution, with the standard second-order Taylor update
+        // and 0.1 min steps instead of the 720 min steps of the standard integrator
+        let (mut taylor_t, mut taylor_mean_motion, mut taylor_lambda) =
+            (0.0, reference_0.mean_motion, reference_0.lambda);
+        let h = 0.1_f64;
+        for _ in 0..((t / h).round() as usize) {
+            let lambda_dot = taylor_mean_motion + lambda_dot_0;
+            let (ni_dot, ni_dot_lambda) = ResonanceState::mean_motion_dot(
+                resonance,
+                constants.orbit_0.argument_of_perigee,
+                taylor_lambda,
+                taylor_t,
+            );
+            taylor_t += h;
+            taylor_mean_motion += ni_dot * h + ni_dot_lambda * lambda_dot * h.powi(2) / 2.0;
+            taylor_lambda += lambda_dot * h + ni_dot * h.powi(2) / 2.0;
+        }
+        assert!((taylor_mean_motion - reference.mean_motion).abs() < 1.0e-15);
+        assert!((taylor_lambda - reference.lambda).abs() < 1.0e-11);
+        let error = |high_precision: bool| {
+            let mut state = constants.initial_state().unwrap();
+            state.set_high_precision(high_precision);
+            let (p28, p29) = state.integrate(
+                constants.geopotential,
+                constants.orbit_0.argument_of_perigee,
+                lambda_dot_0,
+                resonance,
+                constants.sidereal_time_0,
+                t,
+                0.0,
+                0.0,
+            );
+            let sidereal_time =
+                (constants.sidereal_time_0 + t * SIDEREAL_SPEED) % (2.0 * std::f64::consts::PI);
+            (
+                ((constants.geopotential.ke / p28.powf(1.5)) - reference.mean_motion).abs(),
+                (p29 - sidereal_time - reference.lambda).abs(),
+            )
+        };
+        let (standard_mean_motion_error, standard_lambda_error) = error(false);
+        let (mean_motion_error, lambda_error) = error(true);
+        assert!(mean_motion_error < standard_mean_motion_error);
+        assert!(lambda_error < standard_lambda_error);
+        assert!(lambda_error < 1.0e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn propagate_lyddane_improved_mode() -> gp::Result<()> {
+        // low-inclination deep space satellite whose right ascension p₂₂ becomes negative
+        // around 419.5 min, which is where the AFSPC and improved modes diverge:
+        // the AFSPC mode wraps p₂₂ to [0, 2π[ in the Lyddane argument of perigee and jumps,
+        // whereas the improved mode keeps p₂₂ rem 2π and is continuous
+        let test_cases: test_cases::TestCases =
+            toml::from_str(include_str!("../test_cases.toml")).unwrap();
+        let test_case = test_cases
+            .list
+            .iter()
+            .find(|test_case| test_case.line1.starts_with("1 23599U"))
+            .unwrap();
+        let constants = propagator::Constants::from_elements_afspc_compatibility_mode(
+            &gp::Elements::from_tle(None, test_case.line1.as_bytes(), test_case.line2.as_bytes())?,
+        )?;
+
+        // before the divergence, both modes match the Vallado verification states
+        // the verification states were generated in AFSPC mode and there are no published
+        // improved mode reference states for this satellite, so after the divergence
+        // the improved mode is only checked for continuity below
+        let mut checked = 0;
+        for state in &test_case.states {
+            if let test_cases::State::Ok {
+                time,
+                position,
+                velocity,
+                ..
+            } = state
+            {
+                if *time < 419.0 {
+                    let prediction = constants.propagate(*time)?;
+                    for index in 0..3 {
+                        assert!((position[index] - prediction.position[index]).abs() < 1.0e-6);
+                        assert!((velocity[index] - prediction.velocity[index]).abs() < 1.0e-9);
+                    }
+                    checked += 1;
+                }
+            }
+        }
+        assert!(checked > 10);
+
+        // the divergence time, found by bisection
+        let mut low = 400.0;
+        let mut high = 440.0;
+        while high - low > 1.0e-9 {
+            let middle = 0.5 * (low + high);
+            if constants.propagate(middle)?.position
+                == constants
+                    .propagate_afspc_compatibility_mode(middle)?
+                    .position
+            {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        assert!((low - 419.493).abs() < 1.0e-3);
+
+        // across the divergence, the improved mode moves by its velocity times the time step (< 0.1 m)
+        // whereas the AFSPC mode jumps by about 0.9 km
+        let difference = |a: [f64; 3], b: [f64; 3]| [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+        let before = low - 1.0e-7;
+        let after = high + 1.0e-7;
+        assert!(
+            model::norm(&difference(
+                constants.propagate(before)?.position,
+                constants.propagate(after)?.position
+            )) < 1.0e-4
+        );
+        assert!(
+            model::norm(&difference(
+                constants.propagate(before)?.velocity,
+                constants.propagate(after)?.velocity
+            )) < 1.0e-7
+        );
+        assert!(
+            model::norm(&difference(
+                constants
+                    .propagate_afspc_compatibility_mode(before)?
+                    .position,
+                constants
+                    .propagate_afspc_compatibility_mode(after)?
+                    .position
+            )) > 0.5
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,562 @@
+use crate::gp;
+use crate::model;
+use crate::propagator;
+
+// number of fitted parameters (I, Ω, e cos ω, e sin ω, M + ω, n)
+const PARAMETERS: usize = 6;
+
+// maximum number of differential correction iterations
+const MAXIMUM_ITERATIONS: usize = 100;
+
+// finite differences step for the angles and the eccentricity vector
+const STEP: f64 = 1.0e-7;
+
+// relative finite differences step for the mean motion
+const MEAN_MOTION_RELATIVE_STEP: f64 = 1.0e-7;
+
+// a state observation (t in min, position in km, velocity in km.s⁻¹)
+pub(crate) type Observation = (f64, [f64; 3], [f64; 3]);
+
+// solves the linear system a x = b in place with Gaussian elimination and partial pivoting
+// returns None if the matrix is singular
+pub(crate) fn solve<const N: usize>(mut a: [[f64; N]; N], mut b: [f64; N]) -> Option<[f64; N]> {
+    for column in 0..N {
+        let pivot = (column..N)
+            .max_by(|first, second| {
+                a[*first][column]
+                    .abs()
+                    .partial_cmp(&a[*second][column].abs())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or(column);
+        if a[pivot][column].abs() < f64::MIN_POSITIVE || !a[pivot][column].is_finite() {
+            return None;
+        }
+        a.swap(column, pivot);
+        b.swap(column, pivot);
+        let pivot_row = a[column];
+        for row in (column + 1)..N {
+            let factor = a[row][column] / pivot_row[column];
+            for (value, pivot_value) in a[row].iter_mut().zip(pivot_row.iter()).skip(column) {
+                *value -= factor * pivot_value;
+            }
+            b[row] -= factor * b[column];
+        }
+    }
+    let mut x = [0.0; N];
+    for row in (0..N).rev() {
+        let mut sum = b[row];
+        for index in (row + 1)..N {
+            sum -= a[row][index] * x[index];
+        }
+        x[row] = sum / a[row][row];
+    }
+    Some(x)
+}
+
+// returns the argument of the minimum of a unimodal function in [low, high] with a golden-section search
+pub(crate) fn minimize(
+    function: impl Fn(f64) -> f64,
+    mut low: f64,
+    mut high: f64,
+    tolerance: f64,
+) -> f64 {
+    let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let mut first = high - ratio * (high - low);
+    let mut second = low + ratio * (high - low);
+    let mut first_value = function(first);
+    let mut second_value = function(second);
+    while high - low > tolerance {
+        if first_value < second_value {
+            high = second;
+            second = first;
+            second_value = first_value;
+            first = high - ratio * (high - low);
+            first_value = function(first);
+        } else {
+            low = first;
+            first = second;
+            first_value = second_value;
+            second = low + ratio * (high - low);
+            second_value = function(second);
+        }
+    }
+    0.5 * (low + high)
+}
+
+// converts a position (km) and velocity (km.s⁻¹) to two-body osculating elements
+// returns (I, Ω, e cos ω, e sin ω, M + ω, n) with n in rad.min⁻¹
+fn osculating_parameters(
+    geopotential: &model::Geopotential,
+    position: &[f64; 3],
+    velocity: &[f64; 3],
+) -> gp::Result<[f64; PARAMETERS]> {
+    // r and v in earth radii and earth radii.min⁻¹
+    let r = [
+        position[0] / geopotential.ae,
+        position[1] / geopotential.ae,
+        position[2] / geopotential.ae,
+    ];
+    let v = [
+        velocity[0] * 60.0 / geopotential.ae,
+        velocity[1] * 60.0 / geopotential.ae,
+        velocity[2] * 60.0 / geopotential.ae,
+    ];
+    let mu = geopotential.ke.powi(2);
+    let r_norm = model::norm(&r);
+    let v_squared = model::dot(&v, &v);
+    let r_dot_v = model::dot(&r, &v);
+    let h = model::cross(&r, &v);
+    let h_norm = model::norm(&h);
+    let inverse_a = 2.0 / r_norm - v_squared / mu;
+    if inverse_a.is_nan() || inverse_a <= 0.0 || h_norm.is_nan() || h_norm <= 0.0 {
+        return Err(gp::Error::new(
+            "the observed state is not on an elliptic orbit".to_owned(),
+        ));
+    }
+    let inclination = (h[2] / h_norm).clamp(-1.0, 1.0).acos();
+    let right_ascension = if (h[0].powi(2) + h[1].powi(2)).sqrt() < 1.0e-12 * h_norm {
+        0.0
+    } else {
+        h[0].atan2(-h[1])
+    };
+
+    // eccentricity vector in the inertial frame
+    let e = [
+        ((v_squared - mu / r_norm) * r[0] - r_dot_v * v[0]) / mu,
+        ((v_squared - mu / r_norm) * r[1] - r_dot_v * v[1]) / mu,
+        ((v_squared - mu / r_norm) * r[2] - r_dot_v * v[2]) / mu,
+    ];
+
+    // orbit plane basis (p towards the ascending node, q = (h / |h|) × p 90° ahead in the orbit plane)
+    let p = [right_ascension.cos(), right_ascension.sin(), 0.0];
+    let q = model::cross(&model::normalize(&h), &p);
+    let ex = model::dot(&e, &p);
+    let ey = model::dot(&e, &q);
+    let eccentricity = (ex.powi(2) + ey.powi(2)).sqrt();
+    if eccentricity >= 1.0 {
+        return Err(gp::Error::new(
+            "the observed state is not on an elliptic orbit".to_owned(),
+        ));
+    }
+    let argument_of_perigee = ey.atan2(ex);
+
+    // argument of latitude u = ω + ν
+    let u = model::dot(&r, &q).atan2(model::dot(&r, &p));
+    let true_anomaly = u - argument_of_perigee;
+    let eccentric_anomaly = 2.0
+        * (((1.0 - eccentricity) / (1.0 + eccentricity)).sqrt() * (true_anomaly / 2.0).tan())
+            .atan();
+    let mean_anomaly = eccentric_anomaly - eccentricity * eccentric_anomaly.sin();
+    Ok([
+        inclination,
+        right_ascension,
+        ex,
+        ey,
+        mean_anomaly + argument_of_perigee,
+        geopotential.ke * inverse_a.powf(1.5),
+    ])
+}
+
+pub(crate) fn parameters_from_orbit(orbit: &propagator::Orbit) -> [f64; PARAMETERS] {
+    [
+        orbit.inclination,
+        orbit.right_ascension,
+        orbit.eccentricity * orbit.argument_of_perigee.cos(),
+        orbit.eccentricity * orbit.argument_of_perigee.sin(),
+        orbit.mean_anomaly + orbit.argument_of_perigee,
+        orbit.mean_motion,
+    ]
+}
+
+pub(crate) fn orbit_from_parameters(parameters: &[f64; PARAMETERS]) -> propagator::Orbit {
+    let argument_of_perigee = parameters[3].atan2(parameters[2]);
+    propagator::Orbit {
+        inclination: parameters[0],
+        right_ascension: parameters[1].rem_euclid(2.0 * std::f64::consts::PI),
+        eccentricity: (parameters[2].powi(2) + parameters[3].powi(2)).sqrt(),
+        argument_of_perigee: argument_of_perigee.rem_euclid(2.0 * std::f64::consts::PI),
+        mean_anomaly: (parameters[4] - argument_of_perigee).rem_euclid(2.0 * std::f64::consts::PI),
+        mean_motion: parameters[5],
+    }
+}
+
+// returns the weighted residuals (observed - predicted) in km
+// velocity residuals are scaled by 1 / n so that they are commensurate with positions
+fn residuals(
+    geopotential: &model::Geopotential,
+    epoch: f64,
+    parameters: &[f64; PARAMETERS],
+    observations: &[Observation],
+) -> gp::Result<Vec<f64>> {
+    let constants = propagator::Constants::new(
+        geopotential,
+        model::iau_epoch_to_sidereal_time,
+        epoch,
+        0.0,
+        orbit_from_parameters(parameters),
+    )?;
+    let velocity_scale = 60.0 / parameters[5];
+    let mut result = Vec::with_capacity(observations.len() * 6);
+    for (t, position, velocity) in observations {
+        let prediction = constants.propagate(*t)?;
+        result.extend(
+            position
+                .iter()
+                .zip(prediction.position.iter())
+                .map(|(observed, predicted)| observed - predicted),
+        );
+        result.extend(
+            velocity
+                .iter()
+                .zip(prediction.velocity.iter())
+                .map(|(observed, predicted)| (observed - predicted) * velocity_scale),
+        );
+    }
+    Ok(result)
+}
+
+impl<'a> propagator::Constants<'a> {
+    // initializes a propagator with the same geopotential, epoch, drag term and sidereal time
+    // but different Brouwer elements at epoch
+    pub(crate) fn with_orbit(&self, orbit_0: propagator::Orbit) -> gp::Result<Self> {
+        self.with_drag_term_and_orbit(self.drag_term, orbit_0)
+    }
+
+    // initializes a propagator with the same geopotential, epoch and sidereal time
+    // but a different drag term and different Brouwer elements at epoch
+    // the atmospheric parameter override, the Kepler solver and the short-period setting are kept
+    pub(crate) fn with_drag_term_and_orbit(
+        &self,
+        drag_term: f64,
+        orbit_0: propagator::Orbit,
+    ) -> gp::Result<Self> {
+        self.rebuild(drag_term, orbit_0, self.atmospheric_s)
+    }
+
+    // initializes a propagator with the same geopotential, epoch, sidereal time,
+    // Kepler solver and short-period setting, and re-derives the coefficients
+    // from the given drag term, Brouwer elements at epoch and atmospheric parameter override
+    pub(crate) fn rebuild(
+        &self,
+        drag_term: f64,
+        orbit_0: propagator::Orbit,
+        atmospheric_s: Option<f64>,
+    ) -> gp::Result<Self> {
+        Ok(propagator::Constants::new_with_atmospheric_s(
+            self.geopotential,
+            |_| self.sidereal_time_0,
+            self.epoch,
+            drag_term,
+            orbit_0,
+            atmospheric_s,
+        )?
+        .with_kepler_solver(self.kepler_solver)
+        .with_short_period_corrections(self.short_period_corrections))
+    }
+
+    // returns the partial derivatives of the state at t (km, km.s⁻¹)
+    // with respect to the parameters (I, Ω, e cos ω, e sin ω, M + ω, n) at epoch
+    // computed with central finite differences
+    pub(crate) fn state_jacobian(&self, t: f64) -> gp::Result<[[f64; PARAMETERS]; 6]> {
+        let parameters = parameters_from_orbit(&self.orbit_0);
+        let mut jacobian = [[0.0; PARAMETERS]; 6];
+        for parameter in 0..PARAMETERS {
+            let step = if parameter == 5 {
+                parameters[5] * MEAN_MOTION_RELATIVE_STEP
+            } else {
+                STEP
+            };
+            let mut forward = parameters;
+            forward[parameter] += step;
+            let mut backward = parameters;
+            backward[parameter] -= step;
+            let forward_state = self
+                .with_orbit(orbit_from_parameters(&forward))?
+                .propagate(t)?
+                .to_state6();
+            let backward_state = self
+                .with_orbit(orbit_from_parameters(&backward))?
+                .propagate(t)?
+                .to_state6();
+            for (row, (forward_value, backward_value)) in jacobian
+                .iter_mut()
+                .zip(forward_state.iter().zip(backward_state.iter()))
+            {
+                row[parameter] = (forward_value - backward_value) / (2.0 * step);
+            }
+        }
+        Ok(jacobian)
+    }
+}
+
+fn sum_of_squares(values: &[f64]) -> f64 {
+    values.iter().map(|value| value.powi(2)).sum()
+}
+
+// fits the Brouwer elements at epoch with a Levenberg-Marquardt differential correction
+pub(crate) fn fit_orbit(
+    geopotential: &model::Geopotential,
+    epoch: f64,
+    observations: &[Observation],
+) -> gp::Result<propagator::Orbit> {
+    if observations.is_empty() {
+        return Err(gp::Error::new(
+            "at least one observation is required".to_owned(),
+        ));
+    }
+    let mut parameters =
+        osculating_parameters(geopotential, &observations[0].1, &observations[0].2)?;
+
+    // move the first observation's mean longitude back to epoch
+    parameters[4] -= parameters[5] * observations[0].0;
+    let mut current = residuals(geopotential, epoch, &parameters, observations)?;
+    let mut cost = sum_of_squares(&current);
+    let mut damping = 1.0e-3;
+    for _ in 0..MAXIMUM_ITERATIONS {
+        let mut jacobian = [[0.0; PARAMETERS]; PARAMETERS];
+        let mut gradient = [0.0; PARAMETERS];
+        let mut columns = Vec::with_capacity(PARAMETERS);
+        for parameter in 0..PARAMETERS {
+            let step = if parameter == 5 {
+                parameters[5] * MEAN_MOTION_RELATIVE_STEP
+            } else {
+                STEP
+            };
+            let mut perturbed = parameters;
+            perturbed[parameter] += step;
+            let perturbed_residuals = residuals(geopotential, epoch, &perturbed, observations)?;
+
+            // ∂(predicted) / ∂p = - ∂(residuals) / ∂p
+            columns.push(
+                current
+                    .iter()
+                    .zip(perturbed_residuals.iter())
+                    .map(|(value, perturbed_value)| (value - perturbed_value) / step)
+                    .collect::<Vec<f64>>(),
+            );
+        }
+        for row in 0..PARAMETERS {
+            for column in 0..PARAMETERS {
+                jacobian[row][column] = columns[row]
+                    .iter()
+                    .zip(columns[column].iter())
+                    .map(|(first, second)| first * second)
+                    .sum();
+            }
+            gradient[row] = columns[row]
+                .iter()
+                .zip(current.iter())
+                .map(|(first, second)| first * second)
+                .sum();
+        }
+        let mut improved = false;
+        while damping < 1.0e12 {
+            let mut damped = jacobian;
+            for index in 0..PARAMETERS {
+                damped[index][index] += damping * jacobian[index][index].max(f64::MIN_POSITIVE);
+            }
+            if let Some(delta) = solve(damped, gradient) {
+                let mut candidate = parameters;
+                for index in 0..PARAMETERS {
+                    candidate[index] += delta[index];
+                }
+                candidate[0] = candidate[0].clamp(0.0, std::f64::consts::PI);
+                if candidate[5] > 0.0 && candidate[2].powi(2) + candidate[3].powi(2) < 1.0 {
+                    if let Ok(candidate_residuals) =
+                        residuals(geopotential, epoch, &candidate, observations)
+                    {
+                        let candidate_cost = sum_of_squares(&candidate_residuals);
+                        if candidate_cost < cost {
+                            let converged = (cost - candidate_cost) <= 1.0e-12 * cost
+                                || candidate_cost < 1.0e-20;
+                            parameters = candidate;
+                            current = candidate_residuals;
+                            cost = candidate_cost;
+                            damping = (damping / 10.0).max(1.0e-12);
+                            improved = true;
+                            if converged {
+                                return Ok(orbit_from_parameters(&parameters));
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+            damping *= 10.0;
+        }
+        if !improved {
+            break;
+        }
+    }
+    Ok(orbit_from_parameters(&parameters))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        let x = solve(
+            [[2.0, 1.0, -1.0], [-3.0, -1.0, 2.0], [-2.0, 1.0, 2.0]],
+            [8.0, -11.0, -3.0],
+        )
+        .unwrap();
+        assert!((x[0] - 2.0).abs() < 1.0e-12);
+        assert!((x[1] - 3.0).abs() < 1.0e-12);
+        assert!((x[2] + 1.0).abs() < 1.0e-12);
+        assert!(solve([[1.0, 2.0], [2.0, 4.0]], [1.0, 2.0]).is_none());
+    }
+
+    #[test]
+    fn test_state_transition() -> gp::Result<()> {
+        let constants = propagator::Constants::from_elements(&gp::Elements::from_tle(
+            None,
+            "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+            "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+        )?)?;
+        let state_transition = constants.state_transition(0.0)?;
+        for (row, values) in state_transition.iter().enumerate() {
+            for (column, value) in values.iter().enumerate() {
+                let expected = if row == column { 1.0 } else { 0.0 };
+                assert!((value - expected).abs() < 1.0e-6);
+            }
+        }
+
+        // Φ(t) maps a small change of the epoch state to the change of the state at t
+        let state_transition = constants.state_transition(360.0)?;
+        let mut parameters = parameters_from_orbit(&constants.orbit_0);
+        parameters[5] *= 1.0 + 1.0e-6;
+        parameters[0] += 1.0e-6;
+        let perturbed = constants.with_orbit(orbit_from_parameters(&parameters))?;
+        let state_0 = constants.propagate(0.0)?.to_state6();
+        let perturbed_state_0 = perturbed.propagate(0.0)?.to_state6();
+        let state_t = constants.propagate(360.0)?.to_state6();
+        let perturbed_state_t = perturbed.propagate(360.0)?.to_state6();
+        for (row, values) in state_transition.iter().enumerate() {
+            let predicted_change: f64 = values
+                .iter()
+                .zip(perturbed_state_0.iter().zip(state_0.iter()))
+                .map(|(value, (perturbed_value, value_0))| value * (perturbed_value - value_0))
+                .sum();
+            let change = perturbed_state_t[row] - state_t[row];
+            assert!((predicted_change - change).abs() < 1.0e-2 * change.abs().max(1.0e-3));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_propagate_covariance() -> gp::Result<()> {
+        let constants = propagator::Constants::from_elements(&gp::Elements::from_tle(
+            None,
+            "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+            "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+        )?)?;
+
+        // a mean motion variance σ² maps to σ² (∂x / ∂n) (∂x / ∂n)ᵀ
+        let variance = 1.0e-16;
+        let mut p0 = [[0.0; PARAMETERS]; PARAMETERS];
+        p0[5][5] = variance;
+        let covariance = constants.propagate_covariance(1440.0, p0)?;
+        let jacobian = constants.state_jacobian(1440.0)?;
+        for row in 0..6 {
+            for column in 0..6 {
+                let expected = variance * jacobian[row][5] * jacobian[column][5];
+                assert!((covariance[row][column] - expected).abs() <= 1.0e-12 * expected.abs());
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_state_jacobian_without_short_period_corrections() -> gp::Result<()> {
+        let elements = gp::Elements::from_tle(
+            None,
+            "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+            "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+        )?;
+        let constants =
+            propagator::Constants::from_elements(&elements)?.with_short_period_corrections(false);
+        let jacobian = constants.state_jacobian(1440.0)?;
+
+        // the finite differences must be taken with the same (mean elements only) model
+        let parameters = parameters_from_orbit(&constants.orbit_0);
+        let step = parameters[5] * MEAN_MOTION_RELATIVE_STEP;
+        let state = |mean_motion: f64| -> gp::Result<[f64; 6]> {
+            let mut perturbed = parameters;
+            perturbed[5] = mean_motion;
+            Ok(propagator::Constants::new(
+                &model::WGS84,
+                model::iau_epoch_to_sidereal_time,
+                constants.epoch,
+                constants.drag_term,
+                orbit_from_parameters(&perturbed),
+            )?
+            .with_short_period_corrections(false)
+            .propagate(1440.0)?
+            .to_state6())
+        };
+        let forward = state(parameters[5] + step)?;
+        let backward = state(parameters[5] - step)?;
+        for row in 0..6 {
+            assert_eq!(
+                jacobian[row][5],
+                (forward[row] - backward[row]) / (2.0 * step)
+            );
+        }
+        assert!(
+            propagator::Constants::from_elements(&elements)?.state_jacobian(1440.0)? != jacobian
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_fit_orbit() -> gp::Result<()> {
+        let elements = gp::Elements::from_tle(
+            None,
+            "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+            "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+        )?;
+        let orbit_0 = propagator::Orbit::from_kozai_elements(
+            &model::WGS84,
+            elements.inclination * (std::f64::consts::PI / 180.0),
+            elements.right_ascension * (std::f64::consts::PI / 180.0),
+            elements.eccentricity,
+            elements.argument_of_perigee * (std::f64::consts::PI / 180.0),
+            elements.mean_anomaly * (std::f64::consts::PI / 180.0),
+            elements.mean_motion * (std::f64::consts::PI / 720.0),
+        )?;
+        let constants = propagator::Constants::new(
+            &model::WGS84,
+            model::iau_epoch_to_sidereal_time,
+            elements.epoch(),
+            0.0,
+            orbit_0,
+        )?;
+        let observations = (0..10)
+            .map(|index| {
+                let t = index as f64 * 10.0;
+                let prediction = constants.propagate(t)?;
+                Ok((t, prediction.position, prediction.velocity))
+            })
+            .collect::<gp::Result<Vec<Observation>>>()?;
+        let orbit = fit_orbit(&model::WGS84, elements.epoch(), &observations)?;
+        assert!((orbit.mean_motion - constants.orbit_0.mean_motion).abs() < 1.0e-10);
+        let fitted = propagator::Constants::new(
+            &model::WGS84,
+            model::iau_epoch_to_sidereal_time,
+            elements.epoch(),
+            0.0,
+            orbit,
+        )?;
+        for t in &[0.0, 45.0, 90.0, 1440.0] {
+            let expected = constants.propagate(*t)?;
+            let prediction = fitted.propagate(*t)?;
+            for index in 0..3 {
+                assert!((expected.position[index] - prediction.position[index]).abs() < 1.0e-3);
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::propagator;
 use chrono::{Datelike, Timelike};
 use serde::de::Deserialize;
 
@@ -85,16 +86,57 @@ trait DecimalPointAssumedRepresentation {
 impl DecimalPointAssumedRepresentation for [u8] {
     fn parse_decimal_point_assumed(&self) -> Result<f64> {
         let trimmed = std::str::from_utf8(self)?.trim_start();
-        if trimmed.starts_with("-") {
-            Ok(format!("-.{}", &trimmed[1..]).parse::<f64>()?)
-        } else if trimmed.starts_with("+") {
-            Ok(format!(".{}", &trimmed[1..]).parse::<f64>()?)
+        if let Some(digits) = trimmed.strip_prefix('-') {
+            Ok(format!("-.{}", digits).parse::<f64>()?)
+        } else if let Some(digits) = trimmed.strip_prefix('+') {
+            Ok(format!(".{}", digits).parse::<f64>()?)
         } else {
             Ok(format!(".{}", trimmed).parse::<f64>()?)
         }
     }
 }
 
+// parses a fixed-width TLE column, ignoring leading spaces
+fn parse_column<T>(column: &[u8]) -> Result<T>
+where
+    T: std::str::FromStr,
+    Error: From<T::Err>,
+{
+    Ok(std::str::from_utf8(column)?.trim_start().parse()?)
+}
+
+// formats a number in the TLE "decimal point assumed" exponential notation (for example -31515-4)
+fn format_decimal_point_assumed_exponential(value: f64) -> String {
+    if value == 0.0 {
+        return " 00000-0".to_owned();
+    }
+    let mut exponent = value.abs().log10().floor() as i32 + 1;
+    let mut mantissa = (value.abs() / 10.0_f64.powi(exponent) * 1e5).round() as u32;
+    if mantissa >= 100000 {
+        mantissa /= 10;
+        exponent += 1;
+    }
+    format!(
+        "{}{:05}{}{}",
+        if value < 0.0 { '-' } else { ' ' },
+        mantissa,
+        if exponent <= 0 { '-' } else { '+' },
+        exponent.abs()
+    )
+}
+
+// returns the TLE checksum digit of the first 68 characters of a line
+fn checksum(line: &[u8]) -> u8 {
+    (line[..68]
+        .iter()
+        .fold(0, |accumulator, character| match character {
+            b'-' => accumulator + 1,
+            character if character.is_ascii_digit() => accumulator + (character - b'0') as u16,
+            _ => accumulator,
+        })
+        % 10) as u8
+}
+
 /// The result type returned by SGP4 functions
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -175,7 +217,10 @@ pub struct Elements {
     pub classification: Classification,
 
     /// The UTC timestamp of the elements
-    #[serde(rename = "EPOCH")]
+    ///
+    /// OMM epochs can be ISO 8601 calendar or ordinal dates with a four-digit or two-digit year,
+    /// or a TLE day of year (for example "20194.88612269"). Two-digit years 57 to 99 map to 1957 to 1999.
+    #[serde(rename = "EPOCH", deserialize_with = "datetime_or_string")]
     pub datetime: chrono::naive::NaiveDateTime,
 
     /// Time derivative of the mean motion
@@ -273,9 +318,85 @@ where
     }
 }
 
+fn datetime_or_string<'de, D>(
+    deserializer: D,
+) -> std::result::Result<chrono::naive::NaiveDateTime, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    match serde_json::value::Value::deserialize(deserializer)? {
+        serde_json::value::Value::String(string) => {
+            parse_epoch(&string).map_err(serde::de::Error::custom)
+        }
+        _ => Err(serde::de::Error::custom("expected a string")),
+    }
+}
+
+// maps a two-digit year to 1957 - 2056, four-digit years are returned unchanged
+fn parse_year(year: &str) -> Result<i32> {
+    match year.len() {
+        2 => Ok(match year.parse::<u8>()? {
+            year if year < 57 => year as i32 + 2000,
+            year => year as i32 + 1900,
+        }),
+        4 => Ok(year.parse()?),
+        _ => Err(Error::new("invalid epoch year".to_owned())),
+    }
+}
+
+// parses the epoch spellings used by TLEs and OMMs into a UTC timestamp
+// - ISO 8601 calendar date, for example "2020-07-12T01:19:07.402656", with an optional "Z" suffix
+// - ISO 8601 calendar date with a two-digit year, for example "20-07-12T01:19:07.402656"
+// - ISO 8601 ordinal date, for example "2020-194T01:19:07.402656" (two-digit years are accepted)
+// - TLE day of year, a two-digit year followed by the fractional day, for example "20194.05494679"
+pub(crate) fn parse_epoch(epoch: &str) -> Result<chrono::naive::NaiveDateTime> {
+    let epoch = epoch.strip_suffix('Z').unwrap_or(epoch);
+    let (date, time) = match epoch.split_once('T') {
+        Some((date, time)) => {
+            let mut parts = date.split('-');
+            (
+                match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                    (Some(year), Some(month), Some(day), None) => chrono::NaiveDate::from_ymd_opt(
+                        parse_year(year)?,
+                        month.parse()?,
+                        day.parse()?,
+                    ),
+                    (Some(year), Some(day), None, None) => {
+                        chrono::NaiveDate::from_yo_opt(parse_year(year)?, day.parse()?)
+                    }
+                    _ => None,
+                },
+                chrono::NaiveTime::parse_from_str(time, "%H:%M:%S%.f").ok(),
+            )
+        }
+        None => {
+            let (year, day) = match (epoch.get(..2), epoch.get(2..)) {
+                (Some(year), Some(day)) => (year, day),
+                _ => return Err(Error::new("invalid epoch".to_owned())),
+            };
+            let day = day.trim_start().parse::<f64>()?;
+            let seconds = day.fract() * (24.0 * 60.0 * 60.0);
+            (
+                chrono::NaiveDate::from_yo_opt(parse_year(year)?, day as u32),
+                chrono::NaiveTime::from_num_seconds_from_midnight_opt(
+                    seconds as u32,
+                    (seconds.fract() * 1e9).round() as u32,
+                ),
+            )
+        }
+    };
+    match (date, time) {
+        (Some(date), Some(time)) => Ok(date.and_time(time)),
+        _ => Err(Error::new("invalid epoch".to_owned())),
+    }
+}
+
 impl Elements {
     /// Parses a Two-Line Element Set (TLE) with an optionnal title
     ///
+    /// Malformed lines of any length or byte content return an error and never panic
+    /// (see the fuzz target in the repository's `fuzz` directory).
+    ///
     /// # Arguments
     ///
     /// * `object_name` - The name of the satellite, usually given by a third line placed before the TLE
@@ -327,31 +448,18 @@ impl Elements {
                 )));
             }
         }
-        let norad_id = std::str::from_utf8(&line1[2..7])?
-            .trim_start()
-            .parse::<u64>()?;
-        if norad_id
-            != std::str::from_utf8(&line2[2..7])?
-                .trim_start()
-                .parse::<u64>()?
-        {
+        let view = ElementsView {
+            line1: line1,
+            line2: line2,
+        };
+        let norad_id = view.norad_id()?;
+        if norad_id != parse_column::<u64>(&line2[2..7])? {
             return Err(Error::new(
                 "line 1 and 2 have different satellite numbers".to_owned(),
             ));
         }
         for line in &[line1, line2] {
-            if (line[..68]
-                .iter()
-                .fold(0, |accumulator, character| match character {
-                    b'-' => accumulator + 1,
-                    character if character >= &b'0' && character <= &b'9' => {
-                        accumulator + (character - b'0') as u16
-                    }
-                    _ => accumulator,
-                })
-                % 10) as u8
-                != line[68] - b'0'
-            {
+            if checksum(line) + b'0' != line[68] {
                 return Err(Error::new("bad checksum".to_owned()));
             }
         }
@@ -364,10 +472,7 @@ impl Elements {
                 b'S' => Classification::Secret,
                 _ => return Err(Error::new("unknown classification".to_owned())),
             },
-            international_designator: if line1[9..17]
-                .iter()
-                .all(|character| *character == ' ' as u8)
-            {
+            international_designator: if line1[9..17].iter().all(|character| *character == b' ') {
                 None
             } else {
                 Some(format!(
@@ -379,56 +484,117 @@ impl Elements {
                     std::str::from_utf8(&line1[11..17])?.trim()
                 ))
             },
-            datetime: {
-                let day = std::str::from_utf8(&line1[20..32])?
-                    .trim_start()
-                    .parse::<f64>()?;
-                let seconds = day.fract() * (24.0 * 60.0 * 60.0);
-                chrono::NaiveDate::from_yo(
-                    match std::str::from_utf8(&line1[18..20])?.parse::<u8>()? {
-                        year if year < 57 => year as i32 + 2000,
-                        year => year as i32 + 1900,
-                    },
-                    day as u32,
-                )
-                .and_time(chrono::NaiveTime::from_num_seconds_from_midnight(
-                    seconds as u32,
-                    (seconds.fract() * 1e9).round() as u32,
-                ))
-            },
-            mean_motion_dot: std::str::from_utf8(&line1[33..43])?.trim_start().parse()?,
+            datetime: parse_epoch(std::str::from_utf8(&line1[18..32])?)?,
+            mean_motion_dot: parse_column(&line1[33..43])?,
             mean_motion_ddot: line1[44..50].parse_decimal_point_assumed()?
                 * 10.0_f64.powi(std::str::from_utf8(&line1[50..52])?.parse::<i8>()? as i32),
-            drag_term: line1[53..59].parse_decimal_point_assumed()?
-                * 10.0_f64.powi(std::str::from_utf8(&line1[59..61])?.parse::<i8>()? as i32),
-            ephemeris_type: std::str::from_utf8(&line1[62..63])?.trim_start().parse()?,
-            element_set_number: std::str::from_utf8(&line1[64..68])?.trim_start().parse()?,
-            inclination: std::str::from_utf8(&line2[8..16])?.trim_start().parse()?,
-            right_ascension: std::str::from_utf8(&line2[17..25])?.trim_start().parse()?,
-            eccentricity: line2[26..33].parse_decimal_point_assumed()?,
-            argument_of_perigee: std::str::from_utf8(&line2[34..42])?.trim_start().parse()?,
-            mean_anomaly: std::str::from_utf8(&line2[43..51])?.trim_start().parse()?,
-            mean_motion: std::str::from_utf8(&line2[52..63])?.trim_start().parse()?,
-            revolution_number: std::str::from_utf8(&line2[63..68])?.trim_start().parse()?,
+            drag_term: view.drag_term()?,
+            ephemeris_type: parse_column(&line1[62..63])?,
+            element_set_number: parse_column(&line1[64..68])?,
+            inclination: view.inclination()?,
+            right_ascension: view.right_ascension()?,
+            eccentricity: view.eccentricity()?,
+            argument_of_perigee: view.argument_of_perigee()?,
+            mean_anomaly: view.mean_anomaly()?,
+            mean_motion: view.mean_motion()?,
+            revolution_number: parse_column(&line2[63..68])?,
         })
     }
 
+    /// Formats the elements as the two lines of a Two-Line Element Set (TLE)
+    ///
+    /// The classification character, the international designator, the ephemeris type
+    /// and the element set number are written in their fixed columns, and both checksums are recalculated.
+    /// The object name is not part of the two lines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let line1 = "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992";
+    /// let line2 = "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008";
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     line1.as_bytes(),
+    ///     line2.as_bytes(),
+    /// )?;
+    /// assert_eq!(elements.to_lines()?, (line1.to_owned(), line2.to_owned()));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn to_lines(&self) -> Result<(String, String)> {
+        if self.norad_id > 99999 {
+            return Err(Error::new(
+                "the NORAD ID does not fit in the TLE format".to_owned(),
+            ));
+        }
+        let international_designator = match &self.international_designator {
+            Some(international_designator) => {
+                match (
+                    international_designator.get(..4),
+                    international_designator.get(4..5),
+                    international_designator.get(5..),
+                ) {
+                    (Some(year), Some("-"), Some(piece)) if piece.len() <= 6 => {
+                        format!("{:02}{:<6}", year.parse::<u16>()? % 100, piece)
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            "the international designator must have the format YYYY-NNNP{PP}"
+                                .to_owned(),
+                        ))
+                    }
+                }
+            }
+            None => " ".repeat(8),
+        };
+        let mut line1 = format!(
+            "1 {:05}{} {} {:02}{:012.8} {}{} {} {} {} {:>4}",
+            self.norad_id,
+            match self.classification {
+                Classification::Unclassified => 'U',
+                Classification::Classified => 'C',
+                Classification::Secret => 'S',
+            },
+            international_designator,
+            self.datetime.year() % 100,
+            self.datetime.ordinal() as f64
+                + (self.datetime.num_seconds_from_midnight() as f64
+                    + self.datetime.nanosecond() as f64 / 1e9)
+                    / (24.0 * 60.0 * 60.0),
+            if self.mean_motion_dot < 0.0 { '-' } else { ' ' },
+            format!("{:.8}", self.mean_motion_dot.abs()).trim_start_matches('0'),
+            format_decimal_point_assumed_exponential(self.mean_motion_ddot),
+            format_decimal_point_assumed_exponential(self.drag_term),
+            self.ephemeris_type,
+            self.element_set_number % 10000,
+        );
+        let mut line2 = format!(
+            "2 {:05} {:8.4} {:8.4} {:07} {:8.4} {:8.4} {:11.8}{:5}",
+            self.norad_id,
+            self.inclination,
+            self.right_ascension,
+            (self.eccentricity * 1e7).round() as u32,
+            self.argument_of_perigee,
+            self.mean_anomaly,
+            self.mean_motion,
+            self.revolution_number % 100000,
+        );
+        if line1.len() != 68 || line2.len() != 68 {
+            return Err(Error::new(
+                "the elements do not fit in the TLE format".to_owned(),
+            ));
+        }
+        line1.push((b'0' + checksum(format!("{}0", line1).as_bytes())) as char);
+        line2.push((b'0' + checksum(format!("{}0", line2).as_bytes())) as char);
+        Ok((line1, line2))
+    }
+
     /// Returns the number of years since UTC 1 January 2000 12h00 (J2000)
     ///
     /// This is the recommended method to calculate the epoch
     pub fn epoch(&self) -> f64 {
-        // y₂₀₀₀ = (367 yᵤ - ⌊7 (yᵤ + ⌊(mᵤ + 9) / 12⌋) / 4⌋ + 275 ⌊mᵤ / 9⌋ + dᵤ - 730531) / 365.25
-        //         + (3600 hᵤ + 60 minᵤ + sᵤ - 43200) / (24 × 60 × 60 × 365.25)
-        //         + nsᵤ / (24 × 60 × 60 × 365.25 × 10⁹)
-        (367 * self.datetime.year() as i32
-            - (7 * (self.datetime.year() as i32 + (self.datetime.month() as i32 + 9) / 12)) / 4
-            + 275 * self.datetime.month() as i32 / 9
-            + self.datetime.day() as i32
-            - 730531) as f64
-            / 365.25
-            + (self.datetime.num_seconds_from_midnight() as i32 - 43200) as f64
-                / (24.0 * 60.0 * 60.0 * 365.25)
-            + (self.datetime.nanosecond() as f64) / (24.0 * 60.0 * 60.0 * 1e9 * 365.25)
+        propagator::Epoch::from_datetime(&self.datetime).years_since_j2000()
     }
 
     /// Returns the number of years since UTC 1 January 2000 12h00 (J2000) using the AFSPC expression
@@ -453,6 +619,588 @@ impl Elements {
             - 2451545.0)
             / 365.25
     }
+
+    /// Returns the elements' epoch as an ISO 8601 UTC timestamp with microsecond precision
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// assert_eq!(elements.epoch_string(), "2020-07-12T21:16:01.000416Z");
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn epoch_string(&self) -> String {
+        self.datetime.format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string()
+    }
+
+    /// Returns the elements' epoch as a hifitime epoch
+    ///
+    /// This method requires the `hifitime` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let expected = hifitime::Epoch::from_gregorian_utc(2020, 7, 12, 21, 16, 1, 416_000);
+    /// assert!((elements.epoch_hifitime() - expected).abs() < hifitime::Unit::Microsecond * 1);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "hifitime")]
+    pub fn epoch_hifitime(&self) -> hifitime::Epoch {
+        hifitime::Epoch::from_gregorian_utc(
+            self.datetime.year(),
+            self.datetime.month() as u8,
+            self.datetime.day() as u8,
+            self.datetime.hour() as u8,
+            self.datetime.minute() as u8,
+            self.datetime.second() as u8,
+            self.datetime.nanosecond(),
+        )
+    }
+
+    /// Returns the number of minutes elapsed between the elements' epoch and the given UTC timestamp
+    ///
+    /// Leap seconds are ignored, which is consistent with the AFSPC implementation.
+    /// See `Elements::minutes_since_epoch_with_leap_seconds` for a leap-second-aware alternative.
+    ///
+    /// # Arguments
+    ///
+    /// * `datetime` - The UTC timestamp at which the prediction is needed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let constants = sgp4::Constants::from_elements(&elements)?;
+    /// let prediction = constants.propagate(elements.minutes_since_epoch(
+    ///     &chrono::NaiveDate::from_ymd_opt(2020, 7, 13)
+    ///         .unwrap()
+    ///         .and_hms_opt(12, 0, 0)
+    ///         .unwrap(),
+    /// ))?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn minutes_since_epoch(&self, datetime: &chrono::naive::NaiveDateTime) -> f64 {
+        datetime
+            .signed_duration_since(self.datetime)
+            .num_nanoseconds()
+            .map_or_else(
+                || datetime.signed_duration_since(self.datetime).num_seconds() as f64 / 60.0,
+                |nanoseconds| nanoseconds as f64 / 60.0e9,
+            )
+    }
+
+    /// Returns the number of minutes elapsed between the elements' epoch and the given UTC timestamp,
+    /// accounting for the leap seconds inserted in between
+    ///
+    /// SGP4 itself is UTC-based, and the correction only matters at the sub-second level
+    /// when a leap second falls between the epoch and the timestamp.
+    /// A leap second can be represented with chrono as second 59 with 10⁹ nanoseconds or more.
+    ///
+    /// # Arguments
+    ///
+    /// * `datetime` - The UTC timestamp at which the prediction is needed
+    /// * `leap_seconds` - A table of TAI - UTC offsets, for example `sgp4::LEAP_SECONDS`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let constants = sgp4::Constants::from_elements(&elements)?;
+    /// let prediction = constants.propagate(elements.minutes_since_epoch_with_leap_seconds(
+    ///     &chrono::NaiveDate::from_ymd_opt(2020, 7, 13)
+    ///         .unwrap()
+    ///         .and_hms_opt(12, 0, 0)
+    ///         .unwrap(),
+    ///     &sgp4::LEAP_SECONDS,
+    /// ))?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn minutes_since_epoch_with_leap_seconds(
+        &self,
+        datetime: &chrono::naive::NaiveDateTime,
+        leap_seconds: &[(i32, u32, u32, i32)],
+    ) -> f64 {
+        self.minutes_since_epoch(datetime)
+            + (tai_minus_utc(datetime, leap_seconds) - tai_minus_utc(&self.datetime, leap_seconds))
+                as f64
+                / 60.0
+    }
+
+    /// Returns the age of the elements in days at the given time
+    ///
+    /// The age is positive if `at` is after the elements' epoch.
+    /// It is calculated with `Elements::minutes_since_epoch`, hence leap seconds are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `at` - The time at which the age is evaluated, for example `chrono::Utc::now()`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// use chrono::TimeZone;
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let age = elements.age_days(chrono::Utc.with_ymd_and_hms(2020, 7, 22, 21, 16, 1).unwrap());
+    /// assert!((age - 10.0).abs() < 1.0e-6);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn age_days(&self, at: chrono::DateTime<chrono::Utc>) -> f64 {
+        self.minutes_since_epoch(&at.naive_utc()) / (24.0 * 60.0)
+    }
+
+    /// Returns the orbital elements in SI units
+    ///
+    /// The angles are converted to rad and the mean motion to rad.s⁻¹, without changing its (Kozai) convention.
+    /// The semi-major axis is the one used by SGP4: it is derived from the Brouwer mean motion
+    /// calculated with the WGS84 model, a₀" = (kₑ / n₀")²ᐟ³ aₑ.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let elements_si = elements.elements_si()?;
+    /// assert!((elements_si.inclination - 51.6461 * (std::f64::consts::PI / 180.0)).abs() < 1.0e-12);
+    /// assert!((elements_si.semi_major_axis - 6.797e6).abs() < 1.0e3);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn elements_si(&self) -> Result<ElementsSi> {
+        let orbit = propagator::Orbit::from_kozai_elements_without_perigee_check(
+            &crate::model::WGS84,
+            self.inclination * (std::f64::consts::PI / 180.0),
+            self.right_ascension * (std::f64::consts::PI / 180.0),
+            self.eccentricity,
+            self.argument_of_perigee * (std::f64::consts::PI / 180.0),
+            self.mean_anomaly * (std::f64::consts::PI / 180.0),
+            self.mean_motion * (std::f64::consts::PI / 720.0),
+        )?;
+        Ok(ElementsSi {
+            inclination: orbit.inclination,
+            right_ascension: orbit.right_ascension,
+            eccentricity: orbit.eccentricity,
+            argument_of_perigee: orbit.argument_of_perigee,
+            mean_anomaly: orbit.mean_anomaly,
+
+            // n₀ in rad.s⁻¹
+            mean_motion: self.mean_motion * (std::f64::consts::PI / 720.0) / 60.0,
+
+            // a₀" = (kₑ / n₀")²ᐟ³ aₑ in m
+            semi_major_axis: orbit.semi_major_axis(&crate::model::WGS84)
+                * crate::model::WGS84.ae
+                * 1000.0,
+        })
+    }
+
+    /// Returns true if both elements describe the same object
+    ///
+    /// Objects are identified by their NORAD catalog number, regardless of the epoch and the orbital elements.
+    /// If both elements have an international designator, they must also match,
+    /// which distinguishes unrelated objects sharing a catalog number (for example analyst objects).
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The elements to compare with
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::parse_2les(
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992\n\
+    ///      2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008\n\
+    ///      1 42982U 98067NE  20194.06866787  .00008489  00000-0  72204-4 0  9997\n\
+    ///      2 42982  51.6338 155.6245 0002758 166.8841 193.2228 15.70564504154944\n",
+    /// )?;
+    /// assert!(elements[0].same_object(&elements[0]));
+    /// assert!(!elements[0].same_object(&elements[1]));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn same_object(&self, other: &Elements) -> bool {
+        self.norad_id == other.norad_id
+            && match (
+                &self.international_designator,
+                &other.international_designator,
+            ) {
+                (Some(designator), Some(other_designator)) => designator == other_designator,
+                _ => true,
+            }
+    }
+}
+
+/// A borrowed Two-Line Element Set whose fields are parsed on access
+///
+/// Creating a view only checks the lines' lengths and first characters. Each accessor parses
+/// its own columns, which is faster than `Elements::from_tle` when only a few fields are needed,
+/// for example to filter a large catalog by inclination before parsing the selected TLEs.
+/// The accessors do not verify the checksums, `ElementsView::to_elements` performs the full validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElementsView<'a> {
+    line1: &'a [u8],
+    line2: &'a [u8],
+}
+
+impl<'a> ElementsView<'a> {
+    /// Creates a view of a Two-Line Element Set
+    ///
+    /// # Arguments
+    ///
+    /// * `line1` - The first line of the TLE composed of 69 ASCII characters
+    /// * `line2` - The second line of the TLE composed of 69 ASCII characters
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let view = sgp4::ElementsView::new(
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// if view.inclination()? > 50.0 {
+    ///     let elements = view.to_elements(Some("ISS (ZARYA)".to_owned()))?;
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn new(line1: &'a [u8], line2: &'a [u8]) -> Result<ElementsView<'a>> {
+        if line1.len() != 69 || line1[0] != b'1' {
+            return Err(Error::new(
+                "line 1 must have 69 characters and start with the character '1'".to_owned(),
+            ));
+        }
+        if line2.len() != 69 || line2[0] != b'2' {
+            return Err(Error::new(
+                "line 2 must have 69 characters and start with the character '2'".to_owned(),
+            ));
+        }
+        Ok(ElementsView {
+            line1: line1,
+            line2: line2,
+        })
+    }
+
+    /// Parses the NORAD catalog number (line 1)
+    pub fn norad_id(&self) -> Result<u64> {
+        parse_column(&self.line1[2..7])
+    }
+
+    /// Parses the radiation pressure coefficient in earth radii⁻¹
+    pub fn drag_term(&self) -> Result<f64> {
+        Ok(self.line1[53..59].parse_decimal_point_assumed()?
+            * 10.0_f64.powi(std::str::from_utf8(&self.line1[59..61])?.parse::<i8>()? as i32))
+    }
+
+    /// Parses the angle between the equator and the orbit plane in deg
+    pub fn inclination(&self) -> Result<f64> {
+        parse_column(&self.line2[8..16])
+    }
+
+    /// Parses the right ascension of the ascending node in deg
+    pub fn right_ascension(&self) -> Result<f64> {
+        parse_column(&self.line2[17..25])
+    }
+
+    /// Parses the eccentricity
+    pub fn eccentricity(&self) -> Result<f64> {
+        self.line2[26..33].parse_decimal_point_assumed()
+    }
+
+    /// Parses the argument of perigee in deg
+    pub fn argument_of_perigee(&self) -> Result<f64> {
+        parse_column(&self.line2[34..42])
+    }
+
+    /// Parses the mean anomaly in deg
+    pub fn mean_anomaly(&self) -> Result<f64> {
+        parse_column(&self.line2[43..51])
+    }
+
+    /// Parses the Kozai mean motion in rev.day⁻¹
+    pub fn mean_motion(&self) -> Result<f64> {
+        parse_column(&self.line2[52..63])
+    }
+
+    /// Parses and validates all the fields
+    ///
+    /// # Arguments
+    ///
+    /// * `object_name` - The name of the satellite, usually given by a third line placed before the TLE
+    pub fn to_elements(&self, object_name: Option<String>) -> Result<Elements> {
+        Elements::from_tle(object_name, self.line1, self.line2)
+    }
+}
+
+/// A builder for synthetic `Elements`, for example to generate test data
+///
+/// Angles are given in degrees and the mean motion in rev.day⁻¹, as in a TLE.
+/// Fields that are not set default to zero, except the classification which defaults to unclassified.
+/// `ElementsBuilder::build` validates the ranges of the fields,
+/// and `Elements::to_lines` can then format the result as a TLE.
+pub struct ElementsBuilder {
+    elements: Elements,
+}
+
+impl ElementsBuilder {
+    /// Creates a builder with the required fields
+    ///
+    /// # Arguments
+    ///
+    /// * `norad_id` - The satellite catalog number
+    /// * `datetime` - The UTC timestamp of the elements
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::ElementsBuilder::new(
+    ///     99999,
+    ///     chrono::NaiveDate::from_ymd_opt(2020, 7, 12)
+    ///         .unwrap()
+    ///         .and_hms_opt(21, 16, 1)
+    ///         .unwrap(),
+    /// )
+    /// .inclination(51.6461)
+    /// .right_ascension(221.2784)
+    /// .eccentricity(0.0001413)
+    /// .argument_of_perigee(89.1723)
+    /// .mean_anomaly(280.4612)
+    /// .mean_motion(15.49507896)
+    /// .build()?;
+    /// let (line1, line2) = elements.to_lines()?;
+    /// let parsed = sgp4::Elements::from_tle(None, line1.as_bytes(), line2.as_bytes())?;
+    /// assert_eq!(parsed.inclination, 51.6461);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn new(norad_id: u64, datetime: chrono::naive::NaiveDateTime) -> ElementsBuilder {
+        ElementsBuilder {
+            elements: Elements {
+                object_name: None,
+                international_designator: None,
+                norad_id: norad_id,
+                classification: Classification::Unclassified,
+                datetime: datetime,
+                mean_motion_dot: 0.0,
+                mean_motion_ddot: 0.0,
+                drag_term: 0.0,
+                element_set_number: 0,
+                inclination: 0.0,
+                right_ascension: 0.0,
+                eccentricity: 0.0,
+                argument_of_perigee: 0.0,
+                mean_anomaly: 0.0,
+                mean_motion: 0.0,
+                revolution_number: 0,
+                ephemeris_type: 0,
+            },
+        }
+    }
+
+    /// Sets the name associated with the satellite
+    pub fn object_name(mut self, object_name: String) -> ElementsBuilder {
+        self.elements.object_name = Some(object_name);
+        self
+    }
+
+    /// Sets the international designator, with the format YYYY-NNNP{PP}
+    pub fn international_designator(mut self, international_designator: String) -> ElementsBuilder {
+        self.elements.international_designator = Some(international_designator);
+        self
+    }
+
+    /// Sets the elements' classification
+    pub fn classification(mut self, classification: Classification) -> ElementsBuilder {
+        self.elements.classification = classification;
+        self
+    }
+
+    /// Sets the time derivative of the mean motion
+    pub fn mean_motion_dot(mut self, mean_motion_dot: f64) -> ElementsBuilder {
+        self.elements.mean_motion_dot = mean_motion_dot;
+        self
+    }
+
+    /// Sets the second time derivative of the mean motion
+    pub fn mean_motion_ddot(mut self, mean_motion_ddot: f64) -> ElementsBuilder {
+        self.elements.mean_motion_ddot = mean_motion_ddot;
+        self
+    }
+
+    /// Sets the radiation pressure coefficient in earth radii⁻¹
+    pub fn drag_term(mut self, drag_term: f64) -> ElementsBuilder {
+        self.elements.drag_term = drag_term;
+        self
+    }
+
+    /// Sets the element set number
+    pub fn element_set_number(mut self, element_set_number: u64) -> ElementsBuilder {
+        self.elements.element_set_number = element_set_number;
+        self
+    }
+
+    /// Sets the inclination in deg
+    pub fn inclination(mut self, inclination: f64) -> ElementsBuilder {
+        self.elements.inclination = inclination;
+        self
+    }
+
+    /// Sets the right ascension of the ascending node in deg
+    pub fn right_ascension(mut self, right_ascension: f64) -> ElementsBuilder {
+        self.elements.right_ascension = right_ascension;
+        self
+    }
+
+    /// Sets the eccentricity
+    pub fn eccentricity(mut self, eccentricity: f64) -> ElementsBuilder {
+        self.elements.eccentricity = eccentricity;
+        self
+    }
+
+    /// Sets the argument of perigee in deg
+    pub fn argument_of_perigee(mut self, argument_of_perigee: f64) -> ElementsBuilder {
+        self.elements.argument_of_perigee = argument_of_perigee;
+        self
+    }
+
+    /// Sets the mean anomaly in deg
+    pub fn mean_anomaly(mut self, mean_anomaly: f64) -> ElementsBuilder {
+        self.elements.mean_anomaly = mean_anomaly;
+        self
+    }
+
+    /// Sets the Kozai mean motion in rev.day⁻¹
+    pub fn mean_motion(mut self, mean_motion: f64) -> ElementsBuilder {
+        self.elements.mean_motion = mean_motion;
+        self
+    }
+
+    /// Sets the orbit number at epoch
+    pub fn revolution_number(mut self, revolution_number: u64) -> ElementsBuilder {
+        self.elements.revolution_number = revolution_number;
+        self
+    }
+
+    /// Validates the fields and returns the elements
+    ///
+    /// The inclination must be in the range [0, 180] deg, the other angles in the range [0, 360[ deg,
+    /// the eccentricity in the range [0, 1[ and the mean motion must be positive and finite.
+    pub fn build(self) -> Result<Elements> {
+        let elements = self.elements;
+        if !(0.0..=180.0).contains(&elements.inclination) {
+            return Err(Error::new(
+                "the inclination must be in the range [0, 180]".to_owned(),
+            ));
+        }
+        for (name, angle) in [
+            ("right ascension", elements.right_ascension),
+            ("argument of perigee", elements.argument_of_perigee),
+            ("mean anomaly", elements.mean_anomaly),
+        ] {
+            if !(0.0..360.0).contains(&angle) {
+                return Err(Error::new(format!(
+                    "the {} must be in the range [0, 360[",
+                    name
+                )));
+            }
+        }
+        if !(0.0..1.0).contains(&elements.eccentricity) {
+            return Err(Error::new(
+                "the eccentricity must be in the range [0, 1[".to_owned(),
+            ));
+        }
+        if !(elements.mean_motion.is_finite() && elements.mean_motion > 0.0) {
+            return Err(Error::new(
+                "the mean motion must be positive and finite".to_owned(),
+            ));
+        }
+        Ok(elements)
+    }
+}
+
+/// TAI - UTC offsets in seconds and the UTC dates (year, month, day) from which they apply
+///
+/// This table lists the leap seconds introduced between 1972 and 2017 (the latest as of this writing).
+/// It can be extended by the caller and passed to `Elements::minutes_since_epoch_with_leap_seconds`.
+pub const LEAP_SECONDS: [(i32, u32, u32, i32); 28] = [
+    (1972, 1, 1, 10),
+    (1972, 7, 1, 11),
+    (1973, 1, 1, 12),
+    (1974, 1, 1, 13),
+    (1975, 1, 1, 14),
+    (1976, 1, 1, 15),
+    (1977, 1, 1, 16),
+    (1978, 1, 1, 17),
+    (1979, 1, 1, 18),
+    (1980, 1, 1, 19),
+    (1981, 7, 1, 20),
+    (1982, 7, 1, 21),
+    (1983, 7, 1, 22),
+    (1985, 7, 1, 23),
+    (1988, 1, 1, 24),
+    (1990, 1, 1, 25),
+    (1991, 1, 1, 26),
+    (1992, 7, 1, 27),
+    (1993, 7, 1, 28),
+    (1994, 7, 1, 29),
+    (1996, 1, 1, 30),
+    (1997, 7, 1, 31),
+    (1999, 1, 1, 32),
+    (2006, 1, 1, 33),
+    (2009, 1, 1, 34),
+    (2012, 7, 1, 35),
+    (2015, 7, 1, 36),
+    (2017, 1, 1, 37),
+];
+
+// returns TAI - UTC at the given UTC date
+// dates before the first entry use the first offset
+fn tai_minus_utc(
+    datetime: &chrono::naive::NaiveDateTime,
+    leap_seconds: &[(i32, u32, u32, i32)],
+) -> i32 {
+    let date = (datetime.year(), datetime.month(), datetime.day());
+    leap_seconds
+        .iter()
+        .rev()
+        .find(|(year, month, day, _)| (*year, *month, *day) <= date)
+        .or_else(|| leap_seconds.first())
+        .map_or(0, |(_, _, _, offset)| *offset)
 }
 
 /// Parses a multi-line TL/2LE string into a list of `Elements`
@@ -513,6 +1261,211 @@ pub fn parse_3les(tles: &str) -> Result<Vec<Elements>> {
     Ok(elements_group)
 }
 
+/// Reads a TLE file into a list of `Elements`
+///
+/// The format is detected from the first non-empty line: if it starts with `1 `, the file
+/// is parsed as 2LE (see [`parse_2les`]), otherwise as 3LE with object names (see [`parse_3les`]).
+/// Blank lines are ignored.
+///
+/// # Arguments
+///
+/// * `path` - The path of a .tle, .2le or .3le file
+///
+/// # Example
+///
+/// ```no_run
+/// # fn main() -> sgp4::Result<()> {
+/// let elements_group = sgp4::parse_tle_file("stations.tle")?;
+/// for elements in &elements_group {
+///     println!("{}", elements.norad_id);
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+pub fn parse_tle_file<P: AsRef<std::path::Path>>(path: P) -> Result<Vec<Elements>> {
+    let content = std::fs::read_to_string(path)?;
+    let tles = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<&str>>()
+        .join("\n");
+    if tles.starts_with("1 ") {
+        parse_2les(&tles)
+    } else {
+        parse_3les(&tles)
+    }
+}
+
+/// Sorts elements by increasing epoch
+///
+/// The full epoch timestamp (four-digit year, date and time) is compared, so TLEs
+/// from both sides of 2000 are ordered correctly. The sort is stable: elements with
+/// the same epoch keep their relative order.
+///
+/// # Arguments
+///
+/// * `elements_group` - The elements to sort in place, for example parsed from multiple downloads
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> sgp4::Result<()> {
+/// let mut elements_group = sgp4::parse_2les(
+///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992\n\
+///      2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008\n\
+///      1 42982U 98067NE  20194.06866787  .00008489  00000-0  72204-4 0  9997\n\
+///      2 42982  51.6338 155.6245 0002758 166.8841 193.2228 15.70564504154944\n",
+/// )?;
+/// sgp4::sort_by_epoch(&mut elements_group);
+/// assert_eq!(elements_group[0].norad_id, 42982);
+/// #     Ok(())
+/// # }
+/// ```
+pub fn sort_by_epoch(elements_group: &mut [Elements]) {
+    elements_group.sort_by_key(|elements| elements.datetime);
+}
+
+/// Orbital elements converted to SI units
+///
+/// See [sgp4::Elements::elements_si](struct.Elements.html#method.elements_si).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElementsSi {
+    /// Angle between the equator and the orbit plane in rad
+    pub inclination: f64,
+
+    /// Angle between vernal equinox and the point where the orbit crosses the equatorial plane in rad
+    pub right_ascension: f64,
+
+    /// The shape of the orbit
+    pub eccentricity: f64,
+
+    /// Angle between the ascending node and the orbit's point of closest approach to the earth in rad
+    pub argument_of_perigee: f64,
+
+    /// Angle of the satellite location measured from perigee in rad
+    pub mean_anomaly: f64,
+
+    /// Mean orbital angular velocity in rad.s⁻¹ (Kozai convention)
+    pub mean_motion: f64,
+
+    /// Semi-major axis in m (Brouwer convention)
+    pub semi_major_axis: f64,
+}
+
+/// The differences between two catalogs of elements
+///
+/// See [sgp4::diff_catalogs](fn.diff_catalogs.html).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CatalogDiff {
+    /// The NORAD IDs present in the new catalog only, in increasing order
+    pub added: Vec<u64>,
+
+    /// The NORAD IDs present in the old catalog only, in increasing order
+    pub removed: Vec<u64>,
+
+    /// The NORAD IDs present in both catalogs with a more recent epoch in the new catalog, in increasing order
+    pub updated: Vec<u64>,
+}
+
+/// Compares two catalogs of elements, for example two successive downloads
+///
+/// Satellites are identified by their NORAD ID. If a catalog contains several elements
+/// with the same NORAD ID, the most recent epoch is used.
+///
+/// # Arguments
+///
+/// * `old` - The previous catalog
+/// * `new` - The updated catalog
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> sgp4::Result<()> {
+/// let old = sgp4::parse_2les(
+///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992\n\
+///      2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008\n",
+/// )?;
+/// let new = sgp4::parse_2les(
+///     "1 42982U 98067NE  20194.06866787  .00008489  00000-0  72204-4 0  9997\n\
+///      2 42982  51.6338 155.6245 0002758 166.8841 193.2228 15.70564504154944\n",
+/// )?;
+/// let diff = sgp4::diff_catalogs(&old, &new);
+/// assert_eq!(diff.added, vec![42982]);
+/// assert_eq!(diff.removed, vec![25544]);
+/// assert!(diff.updated.is_empty());
+/// #     Ok(())
+/// # }
+/// ```
+pub fn diff_catalogs(old: &[Elements], new: &[Elements]) -> CatalogDiff {
+    let latest_epochs = |catalog: &[Elements]| {
+        let mut epochs = std::collections::BTreeMap::<u64, chrono::naive::NaiveDateTime>::new();
+        for elements in catalog {
+            let epoch = epochs.entry(elements.norad_id).or_insert(elements.datetime);
+            if elements.datetime > *epoch {
+                *epoch = elements.datetime;
+            }
+        }
+        epochs
+    };
+    let old_epochs = latest_epochs(old);
+    let new_epochs = latest_epochs(new);
+    let mut diff = CatalogDiff::default();
+    for (norad_id, new_epoch) in &new_epochs {
+        match old_epochs.get(norad_id) {
+            None => diff.added.push(*norad_id),
+            Some(old_epoch) => {
+                if new_epoch > old_epoch {
+                    diff.updated.push(*norad_id);
+                }
+            }
+        }
+    }
+    diff.removed = old_epochs
+        .keys()
+        .filter(|norad_id| !new_epochs.contains_key(norad_id))
+        .copied()
+        .collect();
+    diff
+}
+
+/// Parses a TLE and propagates it in a single call
+///
+/// This is a shortcut for `Elements::from_tle` followed by `Constants::from_elements` and `Constants::propagate`.
+/// The propagator is initialized on every call, hence `Constants` should be used directly
+/// to propagate the same TLE at multiple times.
+///
+/// # Arguments
+///
+/// * `line1` - The first line of the TLE
+/// * `line2` - The second line of the TLE
+/// * `minutes_since_epoch` - The number of minutes since epoch (can be positive, negative or zero)
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> sgp4::Result<()> {
+/// let prediction = sgp4::propagate_tle(
+///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992",
+///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008",
+///     60.0,
+/// )?;
+/// println!("r = {:?} km", prediction.position);
+/// #     Ok(())
+/// # }
+/// ```
+pub fn propagate_tle(
+    line1: &str,
+    line2: &str,
+    minutes_since_epoch: f64,
+) -> Result<propagator::Prediction> {
+    propagator::Constants::from_elements(&Elements::from_tle(
+        None,
+        line1.as_bytes(),
+        line2.as_bytes(),
+    )?)?
+    .propagate(minutes_since_epoch)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -525,6 +1478,109 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_to_lines() -> Result<()> {
+        for (line1, line2) in &[
+            (
+                "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 4  9996",
+                "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008",
+            ),
+            (
+                "1 29141S 85108AA  06170.26783845  .99999999  00000-0  13519-0 0   718",
+                "2 29141  82.4288 273.4882 0015848 277.2124  83.9133 15.93343074  6828",
+            ),
+            (
+                "1 11801C          80230.29629788  .01431103  00000-0  14311-1 0    13",
+                "2 11801  46.7916 230.4354 7318036  47.4722  10.4117  2.28537848    13",
+            ),
+        ] {
+            let elements = Elements::from_tle(None, line1.as_bytes(), line2.as_bytes())?;
+            let (first, second) = elements.to_lines()?;
+            assert_eq!(&first, line1);
+            assert_eq!(&second, line2);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_elements_view() -> Result<()> {
+        let line1 = "1 11801C          80230.29629788  .01431103  00000-0  14311-1 0    13";
+        let line2 = "2 11801  46.7916 230.4354 7318036  47.4722  10.4117  2.28537848    13";
+        let view = ElementsView::new(line1.as_bytes(), line2.as_bytes())?;
+        let elements = view.to_elements(None)?;
+        assert_eq!(view.norad_id()?, elements.norad_id);
+        assert_eq!(view.drag_term()?, elements.drag_term);
+        assert_eq!(view.inclination()?, elements.inclination);
+        assert_eq!(view.right_ascension()?, elements.right_ascension);
+        assert_eq!(view.eccentricity()?, elements.eccentricity);
+        assert_eq!(view.argument_of_perigee()?, elements.argument_of_perigee);
+        assert_eq!(view.mean_anomaly()?, elements.mean_anomaly);
+        assert_eq!(view.mean_motion()?, elements.mean_motion);
+        assert!(ElementsView::new(&line1.as_bytes()[..68], line2.as_bytes()).is_err());
+        assert!(ElementsView::new(line2.as_bytes(), line1.as_bytes()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_tle_file() -> Result<()> {
+        let line1 = "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992";
+        let line2 = "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008";
+        let path = std::env::temp_dir().join(format!("sgp4-test-{}.tle", std::process::id()));
+        std::fs::write(&path, format!("{}\n{}\n\n", line1, line2))?;
+        let elements_group = parse_tle_file(&path)?;
+        assert_eq!(elements_group.len(), 1);
+        assert!(elements_group[0].object_name.is_none());
+        std::fs::write(&path, format!("ISS (ZARYA)\r\n{}\r\n{}\r\n", line1, line2))?;
+        let elements_group = parse_tle_file(&path)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(elements_group.len(), 1);
+        assert_eq!(
+            elements_group[0].object_name.as_deref(),
+            Some("ISS (ZARYA)")
+        );
+        assert_eq!(elements_group[0].norad_id, 25544);
+        Ok(())
+    }
+
+    #[test]
+    fn test_minutes_since_epoch_with_leap_seconds() -> Result<()> {
+        let mut elements = Elements::from_tle(
+            None,
+            "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+            "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+        )?;
+        elements.datetime = chrono::NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 0, 0)
+            .unwrap();
+        let after = chrono::NaiveDate::from_ymd_opt(2017, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq_f64(elements.minutes_since_epoch(&after), 60.0);
+        assert_eq_f64(
+            elements.minutes_since_epoch_with_leap_seconds(&after, &LEAP_SECONDS),
+            60.0 + 1.0 / 60.0,
+        );
+        let during = chrono::NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_milli_opt(23, 59, 59, 1500)
+            .unwrap();
+        assert_eq_f64(
+            elements.minutes_since_epoch_with_leap_seconds(&during, &LEAP_SECONDS),
+            60.0 + 0.5 / 60.0,
+        );
+        let before = chrono::NaiveDate::from_ymd_opt(2016, 12, 31)
+            .unwrap()
+            .and_hms_opt(22, 0, 0)
+            .unwrap();
+        assert_eq_f64(
+            elements.minutes_since_epoch_with_leap_seconds(&before, &LEAP_SECONDS),
+            -60.0,
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_from_celestrak_omm() -> Result<()> {
         let elements: Elements = serde_json::from_str(
@@ -563,8 +1619,8 @@ mod tests {
         );
         assert_eq!(
             elements.datetime,
-            chrono::NaiveDate::from_yo(2020, 194).and_time(
-                chrono::NaiveTime::from_num_seconds_from_midnight(4747, 402656000)
+            chrono::NaiveDate::from_yo_opt(2020, 194).unwrap().and_time(
+                chrono::NaiveTime::from_num_seconds_from_midnight_opt(4747, 402656000).unwrap()
             )
         );
         assert_eq_f64(elements.epoch(), 20.527186712635181);
@@ -647,8 +1703,8 @@ mod tests {
         );
         assert_eq!(
             elements.datetime,
-            chrono::NaiveDate::from_yo(2020, 348).and_time(
-                chrono::NaiveTime::from_num_seconds_from_midnight(59764, 502592000)
+            chrono::NaiveDate::from_yo_opt(2020, 348).unwrap().and_time(
+                chrono::NaiveTime::from_num_seconds_from_midnight_opt(59764, 502592000).unwrap()
             )
         );
         assert_eq_f64(elements.epoch(), 20.95055912054757);
@@ -735,8 +1791,8 @@ mod tests {
         );
         assert_eq!(
             elements.datetime,
-            chrono::NaiveDate::from_yo(2008, 264).and_time(
-                chrono::NaiveTime::from_num_seconds_from_midnight(44740, 104192001)
+            chrono::NaiveDate::from_yo_opt(2008, 264).unwrap().and_time(
+                chrono::NaiveTime::from_num_seconds_from_midnight_opt(44740, 104192001).unwrap()
             )
         );
         assert_eq_f64(elements.epoch(), 8.720103559972621);
@@ -770,8 +1826,8 @@ mod tests {
         assert!(elements.international_designator.is_none());
         assert_eq!(
             elements.datetime,
-            chrono::NaiveDate::from_yo(1980, 230).and_time(
-                chrono::NaiveTime::from_num_seconds_from_midnight(25600, 136832000)
+            chrono::NaiveDate::from_yo_opt(1980, 230).unwrap().and_time(
+                chrono::NaiveTime::from_num_seconds_from_midnight_opt(25600, 136832000).unwrap()
             )
         );
         assert_eq_f64(elements.epoch(), -19.373589875756331);
@@ -794,6 +1850,70 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_epoch() -> Result<()> {
+        let elements = Elements::from_tle(
+            None,
+            "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+            "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+        )?;
+        assert_eq!(
+            elements.datetime,
+            chrono::NaiveDate::from_ymd_opt(2020, 7, 12)
+                .unwrap()
+                .and_hms_nano_opt(21, 16, 1, 416001)
+                .unwrap()
+        );
+        for epoch in &[
+            "20194.88612269",
+            "2020-07-12T21:16:01.000416001",
+            "2020-07-12T21:16:01.000416001Z",
+            "20-07-12T21:16:01.000416001",
+            "2020-194T21:16:01.000416001",
+            "20-194T21:16:01.000416001",
+        ] {
+            assert_eq!(parse_epoch(epoch)?, elements.datetime);
+            let omm: Elements = serde_json::from_str(&format!(
+                r#"{{
+                    "EPOCH": "{}",
+                    "MEAN_MOTION": 15.49507896,
+                    "ECCENTRICITY": 0.0001413,
+                    "INCLINATION": 51.6461,
+                    "RA_OF_ASC_NODE": 221.2784,
+                    "ARG_OF_PERICENTER": 89.1723,
+                    "MEAN_ANOMALY": 280.4612,
+                    "EPHEMERIS_TYPE": 0,
+                    "CLASSIFICATION_TYPE": "U",
+                    "NORAD_CAT_ID": 25544,
+                    "ELEMENT_SET_NO": 999,
+                    "REV_AT_EPOCH": 23600,
+                    "BSTAR": -0.000031515,
+                    "MEAN_MOTION_DOT": -0.00002218,
+                    "MEAN_MOTION_DDOT": 0
+                }}"#,
+                epoch
+            ))?;
+            assert_eq!(omm.datetime, elements.datetime);
+        }
+        assert_eq!(
+            parse_epoch("57001.5")?,
+            chrono::NaiveDate::from_ymd_opt(1957, 1, 1)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap()
+        );
+        for epoch in &[
+            "",
+            "2",
+            "2020-13-01T00:00:00",
+            "2020-07-12",
+            "202-07-12T00:00:00",
+        ] {
+            assert!(parse_epoch(epoch).is_err());
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_parse_2les() -> Result<()> {
         let elements_group = parse_2les(
@@ -819,4 +1939,51 @@ mod tests {
         assert_eq!(elements_group.len(), 2);
         Ok(())
     }
+
+    #[test]
+    fn test_diff_catalogs() -> Result<()> {
+        let old = parse_2les(
+            "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992\n\
+             2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008\n\
+             1 42982U 98067NE  20194.06866787  .00008489  00000-0  72204-4 0  9997\n\
+             2 42982  51.6338 155.6245 0002758 166.8841 193.2228 15.70564504154944\n",
+        )?;
+        let mut new = parse_2les(
+            "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992\n\
+             2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008\n\
+             1 42982U 98067NE  20194.06866787  .00008489  00000-0  72204-4 0  9997\n\
+             2 42982  51.6338 155.6245 0002758 166.8841 193.2228 15.70564504154944\n",
+        )?;
+        assert_eq!(diff_catalogs(&old, &new), CatalogDiff::default());
+        new[1].datetime += chrono::Duration::hours(12);
+        let diff = diff_catalogs(&old, &new);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert_eq!(diff.updated, vec![42982]);
+
+        // an older epoch is not an update
+        new[1].datetime -= chrono::Duration::days(1);
+        assert!(diff_catalogs(&old, &new).updated.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_tle_malformed() {
+        let line1 = b"1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992";
+        let line2 = b"2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008";
+        for length in 0..line1.len() {
+            assert!(Elements::from_tle(None, &line1[..length], line2).is_err());
+            assert!(Elements::from_tle(None, line1, &line2[..length]).is_err());
+        }
+        for index in 0..line1.len() {
+            for character in &[0u8, b' ', b'+', b'-', b'.', b'0', b'9', b'e', 0x7f, 0xff] {
+                let mut corrupted = *line1;
+                corrupted[index] = *character;
+                let _ = Elements::from_tle(None, &corrupted, line2);
+                let mut corrupted = *line2;
+                corrupted[index] = *character;
+                let _ = Elements::from_tle(None, line1, &corrupted);
+            }
+        }
+    }
 }
@@ -0,0 +1,94 @@
+use crate::gp;
+use crate::propagator;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+// propagates one satellite to all the grid times
+// the times are visited in order of increasing distance to epoch, separately on each side of epoch,
+// so that the resonance integrator state of resonant deep space satellites is never reset
+fn propagate_row(
+    constants: &propagator::Constants,
+    times: &[f64],
+) -> Vec<gp::Result<propagator::Prediction>> {
+    let mut row: Vec<Option<gp::Result<propagator::Prediction>>> =
+        (0..times.len()).map(|_| None).collect();
+    let mut indices = (0..times.len()).collect::<Vec<usize>>();
+    indices.sort_by(|first, second| {
+        times[*first]
+            .abs()
+            .partial_cmp(&times[*second].abs())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let mut positive_state = constants.initial_state();
+    let mut negative_state = constants.initial_state();
+    for index in indices {
+        let t = times[index];
+        row[index] = Some(if t.is_sign_negative() {
+            constants.propagate_from_state(t, negative_state.as_mut(), false)
+        } else {
+            constants.propagate_from_state(t, positive_state.as_mut(), false)
+        });
+    }
+    row.into_iter()
+        .map(|prediction| prediction.expect("every grid time is propagated"))
+        .collect()
+}
+
+/// Propagates a set of satellites to a common grid of times
+///
+/// The result is a satellites × times matrix: `predictions[satellite][time]`
+/// is the prediction of `constants[satellite]` at `times[time]`.
+/// The times are given in minutes since each satellite's epoch, do not need to be sorted,
+/// and can be positive, negative or zero.
+///
+/// Resonant deep space satellites re-use their integrator state between predictions,
+/// which makes this function faster than calling `Constants::propagate` in a loop.
+/// With the `rayon` feature, the satellites are propagated in parallel.
+///
+/// # Arguments
+///
+/// * `constants` - The propagators of the satellites
+/// * `times` - The propagation times in minutes since epoch
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> sgp4::Result<()> {
+/// let constants = sgp4::parse_3les(
+///     "ISS (ZARYA)\n\
+///      1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992\n\
+///      2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008\n",
+/// )?
+/// .iter()
+/// .map(sgp4::Constants::from_elements)
+/// .collect::<sgp4::Result<Vec<_>>>()?;
+/// let times = (0..24).map(|hour| hour as f64 * 60.0).collect::<Vec<f64>>();
+/// for (satellite, predictions) in sgp4::propagate_grid(&constants, &times).iter().enumerate() {
+///     for (t, prediction) in times.iter().zip(predictions.iter()) {
+///         if let Ok(prediction) = prediction {
+///             println!("{} t = {} min, r = {:?} km", satellite, t, prediction.position);
+///         }
+///     }
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+pub fn propagate_grid(
+    constants: &[propagator::Constants],
+    times: &[f64],
+) -> Vec<Vec<gp::Result<propagator::Prediction>>> {
+    #[cfg(feature = "rayon")]
+    {
+        constants
+            .par_iter()
+            .map(|constants| propagate_row(constants, times))
+            .collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        constants
+            .iter()
+            .map(|constants| propagate_row(constants, times))
+            .collect()
+    }
+}
@@ -10,7 +10,7 @@
 //! The following standalone program downloads the lastest stations OMMs from Celestrak
 //! and predicts the stations' positions and velocities after 12 h and 24 h.
 //!
-//! ```
+//! ```no_run
 //! fn main() -> sgp4::Result<()> {
 //!     let response = ureq::get("https://celestrak.com/NORAD/elements/gp.php")
 //!         .query("GROUP", "stations")
@@ -41,28 +41,157 @@
 //! More examples can be found in the repository [https://github.com/neuromorphicsystems/sgp4/tree/master/examples](https://github.com/neuromorphicsystems/sgp4/tree/master/examples).
 //!
 
+// The expressions follow the reference implementation closely,
+// hence the verbatim constants and the long argument lists
+#![allow(
+    clippy::excessive_precision,
+    clippy::manual_range_contains,
+    clippy::redundant_field_names,
+    clippy::too_many_arguments
+)]
+
+mod conjunction;
 mod deep_space;
+mod fit;
 mod gp;
+mod grid;
+mod interpolation;
 mod model;
 mod near_earth;
 mod propagator;
 mod third_body;
 
+pub use conjunction::compare_tles;
+pub use conjunction::screen_conjunctions;
+pub use conjunction::Conjunction;
 pub use deep_space::ResonanceState;
+pub use gp::diff_catalogs;
 pub use gp::parse_2les;
 pub use gp::parse_3les;
+pub use gp::parse_tle_file;
+pub use gp::propagate_tle;
+pub use gp::sort_by_epoch;
+pub use gp::CatalogDiff;
 pub use gp::Classification;
 pub use gp::Elements;
+pub use gp::ElementsBuilder;
+pub use gp::ElementsSi;
+pub use gp::ElementsView;
 pub use gp::Error;
 pub use gp::Result;
+pub use gp::LEAP_SECONDS;
+pub use grid::propagate_grid;
+pub use interpolation::interpolate_hermite;
 pub use model::afspc_epoch_to_sidereal_time;
+pub use model::eccentric_to_true;
+pub use model::epoch_to_sun_direction;
+pub use model::gmst_from_julian_date;
 pub use model::iau_epoch_to_sidereal_time;
+pub use model::kepler_solve;
+pub use model::sun_synchronous_inclination;
+pub use model::teme_to_ecef_matrix;
+pub use model::Ellipsoid;
 pub use model::Geopotential;
 pub use model::WGS72;
 pub use model::WGS84;
+pub use model::WGS84_ELLIPSOID;
+pub use propagator::Anomalies;
+pub use propagator::AtmosphericParameters;
 pub use propagator::Constants;
+pub use propagator::ConstantsSnapshot;
+pub use propagator::DeepSpaceDiagnostics;
+pub use propagator::DerivedQuantities;
+pub use propagator::EcefObserver;
+pub use propagator::EclipseState;
+pub use propagator::ElementsDrift;
+pub use propagator::Epoch;
+pub use propagator::Geodetic;
+pub use propagator::KeplerSolver;
+pub use propagator::LookAngles;
+pub use propagator::MeanElements;
+pub use propagator::OemFrame;
+pub use propagator::OemMetadata;
 pub use propagator::Orbit;
+pub use propagator::OrbitClass;
+pub use propagator::Pass;
+pub use propagator::PerturbationProfile;
 pub use propagator::Prediction;
+pub use propagator::PropagationMethod;
+pub use propagator::PropagationTime;
+pub use propagator::Record;
+pub use propagator::ResonanceKind;
+pub use propagator::SecularRates;
+pub use propagator::StatefulPropagator;
+pub use propagator::Warning;
+pub use propagator::GEO_SEMI_MAJOR_AXIS;
+pub use propagator::GEO_SEMI_MAJOR_AXIS_TOLERANCE;
+pub use propagator::HEO_MIN_ECCENTRICITY;
+pub use propagator::LEO_MAX_ALTITUDE;
+pub use propagator::MAX_GEOCENTRIC_RADIUS;
+pub use propagator::MAX_SAMPLES;
+
+impl Geopotential {
+    /// Creates a geopotential model from normalized spherical harmonic coefficients
+    ///
+    /// Gravity field products such as EGM96 or JGM-3 publish fully normalized zonal coefficients C̄ₙ₀.
+    /// SGP4 uses the un-normalized zonal harmonics Jₙ = -(2n + 1)¹ᐟ² C̄ₙ₀
+    /// and kₑ = 60 (μ / aₑ³)¹ᐟ² in earth radii³ᐟ² min⁻¹.
+    ///
+    /// # Arguments
+    ///
+    /// * `ae` - The equatorial radius of the earth in km
+    /// * `gm` - The earth's gravitational parameter μ in km³.s⁻²
+    /// * `c20` - The normalized second zonal coefficient C̄₂₀
+    /// * `c30` - The normalized third zonal coefficient C̄₃₀
+    /// * `c40` - The normalized fourth zonal coefficient C̄₄₀
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// // EGM96
+    /// let geopotential = sgp4::Geopotential::from_normalized_coefficients(
+    ///     6378.1363,
+    ///     398600.4415,
+    ///     -4.84165371736e-4,
+    ///     9.57161207093e-7,
+    ///     5.39965866638e-7,
+    /// );
+    /// assert!((geopotential.j2 - 1.0826267e-3).abs() < 1.0e-10);
+    /// ```
+    pub fn from_normalized_coefficients(
+        ae: f64,
+        gm: f64,
+        c20: f64,
+        c30: f64,
+        c40: f64,
+    ) -> Geopotential {
+        Geopotential {
+            ae: ae,
+            // kₑ = 60 (μ / aₑ³)¹ᐟ²
+            ke: 60.0 * (gm / ae.powi(3)).sqrt(),
+            // J₂ = -5¹ᐟ² C̄₂₀
+            j2: -(5.0_f64).sqrt() * c20,
+            // J₃ = -7¹ᐟ² C̄₃₀
+            j3: -(7.0_f64).sqrt() * c30,
+            // J₄ = -3 C̄₄₀
+            j4: -3.0 * c40,
+        }
+    }
+
+    /// Returns the earth's gravitational parameter μ in km³.s⁻²
+    ///
+    /// μ = kₑ² aₑ³ / 60² is derived from kₑ, hence it is consistent with the propagator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// assert!((sgp4::WGS84.mu() - 398600.5).abs() < 1.0e-6);
+    /// ```
+    pub fn mu(&self) -> f64 {
+        // μ = kₑ² aₑ³ / 60²
+        self.ke.powi(2) * self.ae.powi(3) / 3600.0
+    }
+}
 
 impl Orbit {
     /// Creates a new Brouwer orbit representation from Kozai elements
@@ -81,6 +210,9 @@ impl Orbit {
     /// * `mean_anomaly` - Angle of the satellite location measured from perigee in rad
     /// * `kozai_mean_motion` - Mean orbital angular velocity in rad.min⁻¹ (Kozai convention)
     ///
+    /// An error is returned if the perigee implied by the Brouwer mean motion is below the Earth's surface,
+    /// which usually indicates corrupted elements.
+    ///
     /// # Example
     ///
     /// ```
@@ -110,6 +242,36 @@ impl Orbit {
         argument_of_perigee: f64,
         mean_anomaly: f64,
         kozai_mean_motion: f64,
+    ) -> Result<Self> {
+        let orbit = Orbit::from_kozai_elements_without_perigee_check(
+            geopotential,
+            inclination,
+            right_ascension,
+            eccentricity,
+            argument_of_perigee,
+            mean_anomaly,
+            kozai_mean_motion,
+        )?;
+
+        // a₀" (1 - e₀) < 1
+        if orbit.semi_major_axis(geopotential) * (1.0 - orbit.eccentricity) < 1.0 {
+            Err(Error::new(
+                "the perigee is below the Earth's surface".to_owned(),
+            ))
+        } else {
+            Ok(orbit)
+        }
+    }
+
+    // the AFSPC implementation flags sub-orbital elements but still propagates them
+    fn from_kozai_elements_without_perigee_check(
+        geopotential: &Geopotential,
+        inclination: f64,
+        right_ascension: f64,
+        eccentricity: f64,
+        argument_of_perigee: f64,
+        mean_anomaly: f64,
+        kozai_mean_motion: f64,
     ) -> Result<Self> {
         if kozai_mean_motion <= 0.0 {
             Err(Error::new(
@@ -155,22 +317,64 @@ impl Orbit {
             }
         }
     }
+
+    // returns the Brouwer semi-major axis a₀" = (kₑ / n₀")²ᐟ³ in earth radii
+    pub(crate) fn semi_major_axis(&self, geopotential: &Geopotential) -> f64 {
+        (geopotential.ke / self.mean_motion).powf(2.0 / 3.0)
+    }
 }
 
-impl<'a> Constants<'a> {
-    /// Initializes a new propagator from epoch quantities
+impl Epoch {
+    /// Creates an epoch from a number of years since UTC 1 January 2000 12h00 (J2000)
     ///
-    /// If the orbital elements are obtained from a TLE or OMM,
-    /// the convenience function [sgp4::Constants::from_elements](struct.Constants.html#method.from_elements)
-    /// can be used instead of manually mapping the `Elements` fields to the `Constants::new` parameters.
+    /// # Arguments
+    ///
+    /// * `years` - The number of Julian years (365.25 days) since J2000
+    pub fn from_years_since_j2000(years: f64) -> Epoch {
+        Epoch {
+            years_since_j2000: years,
+        }
+    }
+
+    /// Creates an epoch from a UTC Julian date
     ///
     /// # Arguments
     ///
-    /// * `geopotential` - The model of Earth gravity to use in the conversion
-    /// * `epoch_to_sidereal_time` - The function to use to convert the J2000 epoch to sidereal time
-    /// * `epoch` - The number of years since UTC 1 January 2000 12h00 (J2000)
-    /// * `drag_term` - The radiation pressure coefficient in earth radii⁻¹ (B*)
-    /// * `orbit_0` - The Brouwer orbital elements at epoch
+    /// * `julian_date` - The number of days since 1 January 4713 BC 12h00
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// assert_eq!(sgp4::Epoch::from_julian_date(2451545.0).years_since_j2000(), 0.0);
+    /// ```
+    pub fn from_julian_date(julian_date: f64) -> Epoch {
+        Epoch {
+            years_since_j2000: (julian_date - 2451545.0) / 365.25,
+        }
+    }
+
+    /// Creates an epoch from a UTC modified Julian date
+    ///
+    /// # Arguments
+    ///
+    /// * `modified_julian_date` - The number of days since 17 November 1858 0h00
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// assert_eq!(sgp4::Epoch::from_modified_julian_date(51544.5).years_since_j2000(), 0.0);
+    /// ```
+    pub fn from_modified_julian_date(modified_julian_date: f64) -> Epoch {
+        Epoch {
+            years_since_j2000: (modified_julian_date - 51544.5) / 365.25,
+        }
+    }
+
+    /// Creates an epoch from a UTC timestamp
+    ///
+    /// # Arguments
+    ///
+    /// * `datetime` - The UTC timestamp of the epoch
     ///
     /// # Example
     ///
@@ -181,213 +385,3998 @@ impl<'a> Constants<'a> {
     ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
     ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
     /// )?;
-    /// let constants = sgp4::Constants::new(
-    ///     &sgp4::WGS84,
-    ///     sgp4::iau_epoch_to_sidereal_time,
-    ///     elements.epoch(),
-    ///     elements.drag_term,
-    ///     sgp4::Orbit::from_kozai_elements(
-    ///         &sgp4::WGS84,
-    ///         elements.inclination * (std::f64::consts::PI / 180.0),
-    ///         elements.right_ascension * (std::f64::consts::PI / 180.0),
-    ///         elements.eccentricity,
-    ///         elements.argument_of_perigee * (std::f64::consts::PI / 180.0),
-    ///         elements.mean_anomaly * (std::f64::consts::PI / 180.0),
-    ///         elements.mean_motion * (std::f64::consts::PI / 720.0),
-    ///     )?,
-    /// )?;
+    /// assert_eq!(
+    ///     sgp4::Epoch::from_datetime(&elements.datetime).years_since_j2000(),
+    ///     elements.epoch()
+    /// );
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn new(
-        geopotential: &'a Geopotential,
-        epoch_to_sidereal_time: impl Fn(f64) -> f64,
-        epoch: f64,
-        drag_term: f64,
-        orbit_0: propagator::Orbit,
-    ) -> Result<Self> {
-        if orbit_0.eccentricity < 0.0 || orbit_0.eccentricity >= 1.0 {
-            Err(Error::new(
-                "the eccentricity must be in the range [0, 1[".to_owned(),
-            ))
-        } else {
-            // p₁ = cos I₀
-            let p1 = orbit_0.inclination.cos();
-
-            // p₂ = 1 − e₀²
-            let p2 = 1.0 - orbit_0.eccentricity.powi(2);
-
-            // k₆ = 3 p₁² - 1
-            let k6 = 3.0 * p1.powi(2) - 1.0;
+    pub fn from_datetime(datetime: &chrono::naive::NaiveDateTime) -> Epoch {
+        use chrono::{Datelike, Timelike};
 
-            // a₀" = (kₑ / n₀")²ᐟ³
-            let a0 = (geopotential.ke / orbit_0.mean_motion).powf(2.0 / 3.0);
+        // y₂₀₀₀ = (367 yᵤ - ⌊7 (yᵤ + ⌊(mᵤ + 9) / 12⌋) / 4⌋ + 275 ⌊mᵤ / 9⌋ + dᵤ - 730531) / 365.25
+        //         + (3600 hᵤ + 60 minᵤ + sᵤ - 43200) / (24 × 60 × 60 × 365.25)
+        //         + nsᵤ / (24 × 60 × 60 × 365.25 × 10⁹)
+        Epoch {
+            years_since_j2000: (367 * datetime.year()
+                - (7 * (datetime.year() + (datetime.month() as i32 + 9) / 12)) / 4
+                + 275 * datetime.month() as i32 / 9
+                + datetime.day() as i32
+                - 730531) as f64
+                / 365.25
+                + (datetime.num_seconds_from_midnight() as i32 - 43200) as f64
+                    / (24.0 * 60.0 * 60.0 * 365.25)
+                + (datetime.nanosecond() as f64) / (24.0 * 60.0 * 60.0 * 1e9 * 365.25),
+        }
+    }
 
-            // p₃ = a₀" (1 - e₀)
-            let p3 = a0 * (1.0 - orbit_0.eccentricity);
-            let (s, p6) = {
-                // p₄ = aₑ (p₃ - 1)
-                let p4 = geopotential.ae * (p3 - 1.0);
-
-                // p₅ = │ 20      if p₄ < 98
-                //      │ p₄ - 78 if 98 ≤ p₄ < 156
-                //      │ 78      otherwise
-                let p5 = if p4 < 98.0 {
-                    20.0
-                } else if p4 < 156.0 {
-                    p4 - 78.0
-                } else {
-                    78.0
-                };
-                (
-                    // s = p₅ / aₑ + 1
-                    p5 / geopotential.ae + 1.0,
-                    // p₆ = ((120 - p₅) / aₑ)⁴
-                    ((120.0 - p5) / geopotential.ae).powi(4),
-                )
-            };
+    /// Returns the number of years since UTC 1 January 2000 12h00 (J2000)
+    pub fn years_since_j2000(&self) -> f64 {
+        self.years_since_j2000
+    }
+}
 
-            // ξ = 1 / (a₀" - s)
-            let xi = 1.0 / (a0 - s);
+impl From<chrono::naive::NaiveDateTime> for Epoch {
+    fn from(datetime: chrono::naive::NaiveDateTime) -> Self {
+        Epoch::from_datetime(&datetime)
+    }
+}
 
-            // p₇ = p₆ ξ⁴
-            let p7 = p6 * xi.powi(4);
+impl PropagationTime {
+    /// Creates a propagation time from a number of minutes since epoch
+    ///
+    /// # Arguments
+    ///
+    /// * `minutes` - The number of minutes since epoch (can be positive, negative or zero)
+    pub fn from_minutes(minutes: f64) -> PropagationTime {
+        PropagationTime { minutes: minutes }
+    }
 
-            // η = a₀" e₀ ξ
-            let eta = a0 * orbit_0.eccentricity * xi;
+    /// Creates a propagation time from a number of seconds since epoch
+    ///
+    /// # Arguments
+    ///
+    /// * `seconds` - The number of seconds since epoch (can be positive, negative or zero)
+    pub fn from_seconds(seconds: f64) -> PropagationTime {
+        PropagationTime {
+            minutes: seconds / 60.0,
+        }
+    }
 
-            // p₈ = |1 - η²|
-            let p8 = (1.0 - eta.powi(2)).abs();
+    /// Creates a propagation time from a number of days since epoch
+    ///
+    /// # Arguments
+    ///
+    /// * `days` - The number of days since epoch (can be positive, negative or zero)
+    pub fn from_days(days: f64) -> PropagationTime {
+        PropagationTime {
+            minutes: days * (24.0 * 60.0),
+        }
+    }
 
-            // p₉ = p₇ / p₈⁷ᐟ²
-            let p9 = p7 / p8.powf(3.5);
+    /// Returns the number of minutes since epoch
+    pub fn minutes(&self) -> f64 {
+        self.minutes
+    }
+}
 
-            // C₁ = B* p₉ n₀" (a₀" (1 + ³/₂ η² + e₀ η (4 + η²))
-            //      + ³/₈ J₂ ξ k₆ (8 + 3 η² (8 + η²)) / p₈)
-            let c1 = drag_term
-                * (p9
-                    * orbit_0.mean_motion
-                    * (a0
-                        * (1.0
-                            + 1.5 * eta.powi(2)
-                            + orbit_0.eccentricity * eta * (4.0 + eta.powi(2)))
-                        + 0.375 * geopotential.j2 * xi / p8
-                            * k6
-                            * (8.0 + 3.0 * eta.powi(2) * (8.0 + eta.powi(2)))));
+impl From<std::time::Duration> for PropagationTime {
+    fn from(duration: std::time::Duration) -> Self {
+        PropagationTime::from_seconds(duration.as_secs_f64())
+    }
+}
 
-            // p₁₀ = (a₀" p₂)⁻²
-            let p10 = 1.0 / (a0 * p2).powi(2);
+impl From<chrono::Duration> for PropagationTime {
+    fn from(duration: chrono::Duration) -> Self {
+        match duration.num_nanoseconds() {
+            Some(nanoseconds) => PropagationTime::from_seconds(nanoseconds as f64 / 1e9),
+            None => PropagationTime::from_seconds(duration.num_seconds() as f64),
+        }
+    }
+}
 
-            // β₀ = p₂¹ᐟ²
-            let b0 = p2.sqrt();
+impl From<Geodetic> for EcefObserver {
+    fn from(geodetic: Geodetic) -> Self {
+        EcefObserver(model::geodetic_to_ecef(
+            geodetic.latitude,
+            geodetic.longitude,
+            geodetic.altitude,
+            model::WGS84.ae,
+            model::WGS84_FLATTENING,
+        ))
+    }
+}
 
-            // p₁₁ = ³/₂ J₂ p₁₀ n₀"
-            let p11 = 1.5 * geopotential.j2 * p10 * orbit_0.mean_motion;
+impl Prediction {
+    /// Returns the position and velocity as a flat state vector
+    ///
+    /// The state is ordered as (x, y, z, ẋ, ẏ, ż), with the position in km
+    /// and the velocity in km.s⁻¹.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let state = constants.propagate(60.0)?.to_state6();
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn to_state6(&self) -> [f64; 6] {
+        [
+            self.position[0],
+            self.position[1],
+            self.position[2],
+            self.velocity[0],
+            self.velocity[1],
+            self.velocity[2],
+        ]
+    }
 
-            // p₁₂ = ¹/₂ p₁₁ J₂ p₁₀
-            let p12 = 0.5 * p11 * geopotential.j2 * p10;
+    /// Creates a prediction from a flat state vector
+    ///
+    /// This is the inverse of `Prediction::to_state6`.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The position in km and the velocity in km.s⁻¹ ordered as (x, y, z, ẋ, ẏ, ż)
+    pub fn from_state6(state: [f64; 6]) -> Prediction {
+        Prediction {
+            position: [state[0], state[1], state[2]],
+            velocity: [state[3], state[4], state[5]],
+        }
+    }
 
-            // p₁₃ = - ¹⁵/₃₂ J₄ p₁₀² n₀"
-            let p13 = -0.46875 * geopotential.j4 * p10.powi(2) * orbit_0.mean_motion;
+    /// Converts the prediction from the True Equator, Mean Equinox frame to the J2000 frame
+    ///
+    /// The conversion applies the equation of the equinoxes, the IAU 1980 nutation
+    /// (truncated to its 30 largest terms, which is accurate to a few milliarcseconds)
+    /// and the IAU 1976 precession (FK5 reduction). Earth orientation corrections
+    /// (δΔψ, δΔε) are neglected, so the result differs from GCRF by about a meter.
+    /// The velocity is rotated with the same matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `jd` - The Julian date of the prediction in terrestrial time (UTC can be used, the difference is negligible)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let constants = sgp4::Constants::from_elements(&elements)?;
+    /// let t = 60.0;
+    /// let prediction = constants
+    ///     .propagate(t)?
+    ///     .to_j2000(2451545.0 + elements.epoch() * 365.25 + t / (24.0 * 60.0));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn to_j2000(&self, jd: f64) -> Prediction {
+        self.rotated(&model::teme_to_j2000_matrix(jd))
+    }
 
-            // p₁₄ = - p₁₁ p₁ + (¹/₂ p₁₂ (4 - 19 p₁²) + 2 p₁₃ (3 - 7 p₁²)) p₁
-            let p14 = -p11 * p1
-                + (0.5 * p12 * (4.0 - 19.0 * p1.powi(2)) + 2.0 * p13 * (3.0 - 7.0 * p1.powi(2)))
-                    * p1;
+    /// Converts the prediction from the True Equator, Mean Equinox frame to the mean equator and equinox of a given date
+    ///
+    /// The prediction is converted to J2000 as in `Prediction::to_j2000`, then precessed
+    /// to the target equinox with the IAU 1976 precession. This is an FK5 conversion:
+    /// for a B1950 equinox (JD 2433282.4235), the result is the FK5 mean equator and equinox of B1950,
+    /// not the FK4 frame (the E-terms of aberration and the FK4 equinox correction are not applied).
+    ///
+    /// # Arguments
+    ///
+    /// * `jd` - The Julian date of the prediction in terrestrial time (UTC can be used, the difference is negligible)
+    /// * `target_jd` - The Julian date of the target equinox, for example the date of observation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let constants = sgp4::Constants::from_elements(&elements)?;
+    /// let t = 60.0;
+    /// let jd = 2451545.0 + elements.epoch() * 365.25 + t / (24.0 * 60.0);
+    /// let prediction = constants.propagate(t)?;
+    /// let b1950 = prediction.to_mean_equinox(jd, 2433282.4235);
+    /// let j2000 = prediction.to_mean_equinox(jd, 2451545.0);
+    /// assert_eq!(j2000.position, prediction.to_j2000(jd).position);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn to_mean_equinox(&self, jd: f64, target_jd: f64) -> Prediction {
+        self.rotated(&model::teme_to_mean_equinox_matrix(jd, target_jd))
+    }
 
-            // k₁₄ = - ¹/₂ p₁₁ (1 - 5 p₁²) + ¹/₁₆ p₁₂ (7 - 114 p₁² + 395 p₁⁴)
-            let k14 = -0.5 * p11 * (1.0 - 5.0 * p1.powi(2))
-                + 0.0625 * p12 * (7.0 - 114.0 * p1.powi(2) + 395.0 * p1.powi(4))
-                + p13 * (3.0 - 36.0 * p1.powi(2) + 49.0 * p1.powi(4));
+    // returns the prediction with its position and velocity rotated by the matrix
+    fn rotated(&self, matrix: &[[f64; 3]; 3]) -> Prediction {
+        Prediction {
+            position: model::rotate(matrix, &self.position),
+            velocity: model::rotate(matrix, &self.velocity),
+        }
+    }
 
-            // p₁₅ = n₀" + ¹/₂ p₁₁ β₀ k₆ + ¹/₁₆ p₁₂ β₀ (13 - 78 p₁² + 137 p₁⁴)
-            let p15 = orbit_0.mean_motion
-                + 0.5 * p11 * b0 * k6
+    /// Converts the prediction's position to geodetic coordinates on the WGS84 ellipsoid
+    ///
+    /// The WGS84 ellipsoid is used whatever the geopotential model of the propagator that made the prediction.
+    /// See `Prediction::to_geodetic_on` for other ellipsoids.
+    ///
+    /// # Arguments
+    ///
+    /// * `gmst` - The Greenwich sidereal time at the prediction time in rad
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let constants = sgp4::Constants::from_elements(&elements)?;
+    /// let t = 60.0;
+    /// let geodetic = constants.propagate(t)?.to_geodetic(sgp4::iau_epoch_to_sidereal_time(
+    ///     elements.epoch() + t / (365.25 * 24.0 * 60.0),
+    /// ));
+    /// assert!(geodetic.latitude.abs() <= 51.7 * (std::f64::consts::PI / 180.0));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn to_geodetic(&self, gmst: f64) -> Geodetic {
+        self.to_geodetic_on(gmst, model::WGS84_ELLIPSOID)
+    }
+
+    /// Converts the prediction's position to geodetic coordinates on a custom ellipsoid
+    ///
+    /// The position is rotated to the Earth-fixed frame with `teme_to_ecef_matrix`,
+    /// then the geodetic latitude and altitude are solved iteratively on the given ellipsoid.
+    ///
+    /// # Arguments
+    ///
+    /// * `gmst` - The sidereal time of the prime meridian at the prediction time in rad
+    /// * `ellipsoid` - The reference ellipsoid
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let sphere = sgp4::Ellipsoid {
+    ///     equatorial_radius: 6371.0,
+    ///     flattening: 0.0,
+    /// };
+    /// let prediction = constants.propagate(60.0)?;
+    /// let geodetic = prediction.to_geodetic_on(0.0, sphere);
+    /// assert!((geodetic.altitude - (prediction.radius() - 6371.0)).abs() < 1.0e-6);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn to_geodetic_on(&self, gmst: f64, ellipsoid: Ellipsoid) -> Geodetic {
+        let position = model::rotate(&model::teme_to_ecef_matrix(gmst), &self.position);
+        let (latitude, longitude, altitude) =
+            model::ecef_to_geodetic(&position, ellipsoid.equatorial_radius, ellipsoid.flattening);
+        Geodetic {
+            latitude: latitude,
+            longitude: longitude,
+            altitude: altitude,
+        }
+    }
+
+    /// Returns the specific orbital energy in km².s⁻²
+    ///
+    /// The two-body energy ε = v² / 2 - μ / r is constant on a Keplerian orbit. SGP4 predictions
+    /// include perturbations, so ε oscillates (mostly with J₂ over an orbit) and drifts with drag,
+    /// but large jumps indicate an inconsistent state. The semi-major axis is a = -μ / (2 ε).
+    ///
+    /// # Arguments
+    ///
+    /// * `geopotential` - The model of Earth gravity used by the propagator
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let first = constants.propagate(0.0)?.specific_energy(&sgp4::WGS84);
+    /// let second = constants.propagate(60.0)?.specific_energy(&sgp4::WGS84);
+    /// assert!(((second - first) / first).abs() < 1.0e-2);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn specific_energy(&self, geopotential: &Geopotential) -> f64 {
+        // ε = v² / 2 - μ / r
+        model::dot(&self.velocity, &self.velocity) / 2.0
+            - geopotential.mu() / model::norm(&self.position)
+    }
+
+    /// Returns the velocity in the radial, along-track, cross-track (RSW) frame in km.s⁻¹
+    ///
+    /// R is along the position, W is along the angular momentum r × v, and S = W × R completes
+    /// the right-handed frame (along the velocity for circular orbits). This is the RIC frame used by `compare_tles`.
+    /// The along-track component is the transverse velocity |r × v| / r and the cross-track component is zero,
+    /// so the decomposition is mostly useful to express maneuvers or to compare with velocities in the same frame.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let [radial, along_track, cross_track] = constants.propagate(60.0)?.velocity_rsw();
+    /// assert!(radial.abs() < 0.1 && along_track > 7.0 && cross_track.abs() < 1.0e-9);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn velocity_rsw(&self) -> [f64; 3] {
+        let [radial, along_track, cross_track] = self.ric_basis();
+        [
+            model::dot(&radial, &self.velocity),
+            model::dot(&along_track, &self.velocity),
+            model::dot(&cross_track, &self.velocity),
+        ]
+    }
+
+    // returns the radial, in-track and cross-track unit vectors
+    // R = r / |r|, C = (r × v) / |r × v|, I = C × R
+    pub(crate) fn ric_basis(&self) -> [[f64; 3]; 3] {
+        let radial = model::normalize(&self.position);
+        let cross_track = model::normalize(&model::cross(&self.position, &self.velocity));
+        let in_track = model::cross(&cross_track, &radial);
+        [radial, in_track, cross_track]
+    }
+
+    /// Returns the geocentric distance in km
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let radius = constants.propagate(60.0)?.radius();
+    /// assert!(radius > 6700.0 && radius < 6850.0);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn radius(&self) -> f64 {
+        // |r| = (x² + y² + z²)¹ᐟ²
+        model::norm(&self.position)
+    }
+
+    /// Returns the geodetic altitude above the WGS84 ellipsoid in km
+    ///
+    /// The WGS84 ellipsoid is used whatever the geopotential model of the propagator that made the prediction.
+    /// The ellipsoid is symmetric about the polar axis, so the altitude does not depend
+    /// on the Earth rotation angle and can be calculated directly from the TEME position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let altitude = constants.propagate(60.0)?.altitude();
+    /// assert!(altitude > 400.0 && altitude < 440.0);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn altitude(&self) -> f64 {
+        model::ellipsoid_height(&self.position, model::WGS84.ae, model::WGS84_FLATTENING)
+    }
+
+    /// Converts the prediction to Brouwer mean elements at the prediction time
+    ///
+    /// The osculating state is inverted with the differential correction used by
+    /// `Constants::fit_from_observations`, with the prediction as the single observation
+    /// at the new epoch `epoch + t`. The returned orbit can be used with `Constants::new`
+    /// to re-epoch a propagator, in which case the drag term should be set to zero
+    /// since it is not estimated.
+    ///
+    /// # Arguments
+    ///
+    /// * `geopotential` - The model of Earth gravity to use in the conversion
+    /// * `t` - The number of minutes between `epoch` and the prediction
+    /// * `epoch` - The number of years since UTC 1 January 2000 12h00 (J2000) of the propagator that produced the prediction
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let constants = sgp4::Constants::from_elements(&elements)?;
+    /// let t = 720.0;
+    /// let prediction = constants.propagate(t)?;
+    /// let orbit = prediction.to_orbit(&sgp4::WGS84, t, elements.epoch())?;
+    /// let re_epoched = sgp4::Constants::new(
+    ///     &sgp4::WGS84,
+    ///     sgp4::iau_epoch_to_sidereal_time,
+    ///     elements.epoch() + t / (365.25 * 24.0 * 60.0),
+    ///     0.0,
+    ///     orbit,
+    /// )?;
+    /// let position = re_epoched.propagate(0.0)?.position;
+    /// assert!((0..3).all(|index| (position[index] - prediction.position[index]).abs() < 1.0e-3));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn to_orbit(&self, geopotential: &Geopotential, t: f64, epoch: f64) -> Result<Orbit> {
+        fit::fit_orbit(
+            geopotential,
+            epoch + t / (365.25 * 24.0 * 60.0),
+            &[(0.0, self.position, self.velocity)],
+        )
+    }
+
+    /// Returns the topocentric right ascension and declination in rad seen from a ground observer
+    ///
+    /// The observer's WGS84 position is rotated from the Earth-fixed frame to TEME,
+    /// and the observer-to-satellite vector is projected on the celestial sphere.
+    /// The angles are therefore relative to the true equator and the mean equinox of date.
+    /// The right ascension is in the range [0, 2π[ and the declination in the range [-π/2, π/2].
+    /// Polar motion, aberration and atmospheric refraction are neglected.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The observer's geodetic position
+    /// * `gmst` - The sidereal time of the prime meridian at the prediction time in rad
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let observer = sgp4::Geodetic {
+    ///     latitude: 48.8566 * (std::f64::consts::PI / 180.0),
+    ///     longitude: 2.3522 * (std::f64::consts::PI / 180.0),
+    ///     altitude: 0.035,
+    /// };
+    /// let (right_ascension, declination) = constants.propagate(60.0)?.to_radec(observer, 0.0);
+    /// assert!((0.0..2.0 * std::f64::consts::PI).contains(&right_ascension));
+    /// assert!(declination.abs() <= std::f64::consts::FRAC_PI_2);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn to_radec(&self, observer: Geodetic, gmst: f64) -> (f64, f64) {
+        let observer_ecef = model::geodetic_to_ecef(
+            observer.latitude,
+            observer.longitude,
+            observer.altitude,
+            model::WGS84.ae,
+            model::WGS84_FLATTENING,
+        );
+
+        // the Earth-fixed to TEME rotation is the transpose of the TEME to Earth-fixed rotation
+        let matrix = model::teme_to_ecef_matrix(gmst);
+        let relative_position = [
+            self.position[0] - (matrix[0][0] * observer_ecef[0] + matrix[1][0] * observer_ecef[1]),
+            self.position[1] - (matrix[0][1] * observer_ecef[0] + matrix[1][1] * observer_ecef[1]),
+            self.position[2] - observer_ecef[2],
+        ];
+        let range = model::norm(&relative_position);
+        (
+            // α = atan2(ρy, ρx)
+            relative_position[1]
+                .atan2(relative_position[0])
+                .rem_euclid(2.0 * std::f64::consts::PI),
+            // δ = asin(ρz / ρ)
+            (relative_position[2] / range).asin(),
+        )
+    }
+}
+
+impl<'a> Constants<'a> {
+    /// Initializes a new propagator from epoch quantities
+    ///
+    /// If the orbital elements are obtained from a TLE or OMM,
+    /// the convenience function [sgp4::Constants::from_elements](struct.Constants.html#method.from_elements)
+    /// can be used instead of manually mapping the `Elements` fields to the `Constants::new` parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `geopotential` - The model of Earth gravity to use in the conversion
+    /// * `epoch_to_sidereal_time` - The function to use to convert the J2000 epoch to sidereal time
+    /// * `epoch` - The number of years since UTC 1 January 2000 12h00 (J2000)
+    /// * `drag_term` - The radiation pressure coefficient in earth radii⁻¹ (B*)
+    /// * `orbit_0` - The Brouwer orbital elements at epoch
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let constants = sgp4::Constants::new(
+    ///     &sgp4::WGS84,
+    ///     sgp4::iau_epoch_to_sidereal_time,
+    ///     elements.epoch(),
+    ///     elements.drag_term,
+    ///     sgp4::Orbit::from_kozai_elements(
+    ///         &sgp4::WGS84,
+    ///         elements.inclination * (std::f64::consts::PI / 180.0),
+    ///         elements.right_ascension * (std::f64::consts::PI / 180.0),
+    ///         elements.eccentricity,
+    ///         elements.argument_of_perigee * (std::f64::consts::PI / 180.0),
+    ///         elements.mean_anomaly * (std::f64::consts::PI / 180.0),
+    ///         elements.mean_motion * (std::f64::consts::PI / 720.0),
+    ///     )?,
+    /// )?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn new(
+        geopotential: &'a Geopotential,
+        epoch_to_sidereal_time: impl Fn(f64) -> f64,
+        epoch: f64,
+        drag_term: f64,
+        orbit_0: propagator::Orbit,
+    ) -> Result<Self> {
+        Constants::new_with_atmospheric_s(
+            geopotential,
+            epoch_to_sidereal_time,
+            epoch,
+            drag_term,
+            orbit_0,
+            None,
+        )
+    }
+
+    // returns the atmospheric parameter s and the density term p₆ = (q₀ - s)⁴
+    // s is derived from the perigee p₃ (in earth radii) unless atmospheric_s overrides it
+    fn atmospheric_s_and_p6(
+        geopotential: &Geopotential,
+        p3: f64,
+        atmospheric_s: Option<f64>,
+    ) -> (f64, f64) {
+        if let Some(s) = atmospheric_s {
+            (
+                s,
+                // p₆ = (q₀ - s)⁴ with q₀ = 120 / aₑ + 1
+                (120.0 / geopotential.ae + 1.0 - s).powi(4),
+            )
+        } else {
+            // p₄ = aₑ (p₃ - 1)
+            let p4 = geopotential.ae * (p3 - 1.0);
+
+            // p₅ = │ 20      if p₄ < 98
+            //      │ p₄ - 78 if 98 ≤ p₄ < 156
+            //      │ 78      otherwise
+            let p5 = if p4 < 98.0 {
+                20.0
+            } else if p4 < 156.0 {
+                p4 - 78.0
+            } else {
+                78.0
+            };
+            (
+                // s = p₅ / aₑ + 1
+                p5 / geopotential.ae + 1.0,
+                // p₆ = ((120 - p₅) / aₑ)⁴
+                ((120.0 - p5) / geopotential.ae).powi(4),
+            )
+        }
+    }
+
+    // initializes a propagator, optionally replacing the perigee-based atmospheric parameter s
+    fn new_with_atmospheric_s(
+        geopotential: &'a Geopotential,
+        epoch_to_sidereal_time: impl Fn(f64) -> f64,
+        epoch: f64,
+        drag_term: f64,
+        orbit_0: propagator::Orbit,
+        atmospheric_s: Option<f64>,
+    ) -> Result<Self> {
+        if orbit_0.eccentricity < 0.0 || orbit_0.eccentricity >= 1.0 {
+            Err(Error::new(
+                "the eccentricity must be in the range [0, 1[".to_owned(),
+            ))
+        } else {
+            // p₁ = cos I₀
+            let p1 = orbit_0.inclination.cos();
+
+            // p₂ = 1 − e₀²
+            let p2 = 1.0 - orbit_0.eccentricity.powi(2);
+
+            // k₆ = 3 p₁² - 1
+            let k6 = 3.0 * p1.powi(2) - 1.0;
+
+            // a₀" = (kₑ / n₀")²ᐟ³
+            let a0 = orbit_0.semi_major_axis(geopotential);
+
+            // p₃ = a₀" (1 - e₀)
+            let p3 = a0 * (1.0 - orbit_0.eccentricity);
+            if let Some(s) = atmospheric_s {
+                if !s.is_finite() || s >= a0 {
+                    return Err(Error::new(
+                        "the atmospheric parameter s must be smaller than the semi-major axis"
+                            .to_owned(),
+                    ));
+                }
+            }
+            let (s, p6) = Constants::atmospheric_s_and_p6(geopotential, p3, atmospheric_s);
+
+            // ξ = 1 / (a₀" - s)
+            let xi = 1.0 / (a0 - s);
+
+            // p₇ = p₆ ξ⁴
+            let p7 = p6 * xi.powi(4);
+
+            // η = a₀" e₀ ξ
+            let eta = a0 * orbit_0.eccentricity * xi;
+
+            // p₈ = |1 - η²|
+            let p8 = (1.0 - eta.powi(2)).abs();
+
+            // p₉ = p₇ / p₈⁷ᐟ²
+            let p9 = p7 / p8.powf(3.5);
+
+            // C₁ = B* p₉ n₀" (a₀" (1 + ³/₂ η² + e₀ η (4 + η²))
+            //      + ³/₈ J₂ ξ k₆ (8 + 3 η² (8 + η²)) / p₈)
+            let c1 = drag_term
+                * (p9
+                    * orbit_0.mean_motion
+                    * (a0
+                        * (1.0
+                            + 1.5 * eta.powi(2)
+                            + orbit_0.eccentricity * eta * (4.0 + eta.powi(2)))
+                        + 0.375 * geopotential.j2 * xi / p8
+                            * k6
+                            * (8.0 + 3.0 * eta.powi(2) * (8.0 + eta.powi(2)))));
+
+            // p₁₀ = (a₀" p₂)⁻²
+            let p10 = 1.0 / (a0 * p2).powi(2);
+
+            // β₀ = p₂¹ᐟ²
+            let b0 = p2.sqrt();
+
+            // p₁₁ = ³/₂ J₂ p₁₀ n₀"
+            let p11 = 1.5 * geopotential.j2 * p10 * orbit_0.mean_motion;
+
+            // p₁₂ = ¹/₂ p₁₁ J₂ p₁₀
+            let p12 = 0.5 * p11 * geopotential.j2 * p10;
+
+            // p₁₃ = - ¹⁵/₃₂ J₄ p₁₀² n₀"
+            let p13 = -0.46875 * geopotential.j4 * p10.powi(2) * orbit_0.mean_motion;
+
+            // p₁₄ = - p₁₁ p₁ + (¹/₂ p₁₂ (4 - 19 p₁²) + 2 p₁₃ (3 - 7 p₁²)) p₁
+            let p14 = -p11 * p1
+                + (0.5 * p12 * (4.0 - 19.0 * p1.powi(2)) + 2.0 * p13 * (3.0 - 7.0 * p1.powi(2)))
+                    * p1;
+
+            // k₁₄ = - ¹/₂ p₁₁ (1 - 5 p₁²) + ¹/₁₆ p₁₂ (7 - 114 p₁² + 395 p₁⁴)
+            let k14 = -0.5 * p11 * (1.0 - 5.0 * p1.powi(2))
+                + 0.0625 * p12 * (7.0 - 114.0 * p1.powi(2) + 395.0 * p1.powi(4))
+                + p13 * (3.0 - 36.0 * p1.powi(2) + 49.0 * p1.powi(4));
+
+            // p₁₅ = n₀" + ¹/₂ p₁₁ β₀ k₆ + ¹/₁₆ p₁₂ β₀ (13 - 78 p₁² + 137 p₁⁴)
+            let p15 = orbit_0.mean_motion
+                + 0.5 * p11 * b0 * k6
                 + 0.0625 * p12 * b0 * (13.0 - 78.0 * p1.powi(2) + 137.0 * p1.powi(4));
 
-            // C₄ = 2 B* n₀" p₉ a₀" p₂ (
-            //      η (2 + ¹/₂ η²)
-            //      + e₀ (¹/₂ + 2 η²)
-            //      - J₂ ξ / (a p₈) (-3 k₆ (1 - 2 e₀ η + η² (³/₂ - ¹/₂ e₀ η))
-            //      + ³/₄ (1 - p₁²) (2 η² - e₀ η (1 + η²)) cos 2 ω₀)
-            let c4 = drag_term
-                * (2.0
-                    * orbit_0.mean_motion
-                    * p9
-                    * a0
-                    * p2
-                    * (eta * (2.0 + 0.5 * eta.powi(2))
-                        + orbit_0.eccentricity * (0.5 + 2.0 * eta.powi(2))
-                        - geopotential.j2 * xi / (a0 * p8)
-                            * (-3.0
-                                * k6
-                                * (1.0 - 2.0 * orbit_0.eccentricity * eta
-                                    + eta.powi(2) * (1.5 - 0.5 * orbit_0.eccentricity * eta))
-                                + 0.75
-                                    * (1.0 - p1.powi(2))
-                                    * (2.0 * eta.powi(2)
-                                        - orbit_0.eccentricity * eta * (1.0 + eta.powi(2)))
-                                    * (2.0 * orbit_0.argument_of_perigee).cos())));
+            // C₄ = 2 B* n₀" p₉ a₀" p₂ (
+            //      η (2 + ¹/₂ η²)
+            //      + e₀ (¹/₂ + 2 η²)
+            //      - J₂ ξ / (a p₈) (-3 k₆ (1 - 2 e₀ η + η² (³/₂ - ¹/₂ e₀ η))
+            //      + ³/₄ (1 - p₁²) (2 η² - e₀ η (1 + η²)) cos 2 ω₀)
+            let c4 = drag_term
+                * (2.0
+                    * orbit_0.mean_motion
+                    * p9
+                    * a0
+                    * p2
+                    * (eta * (2.0 + 0.5 * eta.powi(2))
+                        + orbit_0.eccentricity * (0.5 + 2.0 * eta.powi(2))
+                        - geopotential.j2 * xi / (a0 * p8)
+                            * (-3.0
+                                * k6
+                                * (1.0 - 2.0 * orbit_0.eccentricity * eta
+                                    + eta.powi(2) * (1.5 - 0.5 * orbit_0.eccentricity * eta))
+                                + 0.75
+                                    * (1.0 - p1.powi(2))
+                                    * (2.0 * eta.powi(2)
+                                        - orbit_0.eccentricity * eta * (1.0 + eta.powi(2)))
+                                    * (2.0 * orbit_0.argument_of_perigee).cos())));
+
+            // k₀ = - ⁷/₂ p₂ p₁₁ p₁ C₁
+            let k0 = 3.5 * p2 * (-p11 * p1) * c1;
+
+            // k₁ = ³/₂ C₁
+            let k1 = 1.5 * c1;
+
+            if orbit_0.mean_motion > 2.0 * std::f64::consts::PI / 225.0 {
+                Ok(near_earth::constants(
+                    geopotential,
+                    epoch,
+                    epoch_to_sidereal_time(epoch),
+                    drag_term,
+                    orbit_0,
+                    p1,
+                    a0,
+                    s,
+                    atmospheric_s,
+                    xi,
+                    eta,
+                    c1,
+                    c4,
+                    k0,
+                    k1,
+                    k6,
+                    k14,
+                    p2,
+                    p3,
+                    p7,
+                    p9,
+                    p14,
+                    p15,
+                ))
+            } else {
+                Ok(deep_space::constants(
+                    geopotential,
+                    epoch_to_sidereal_time,
+                    epoch,
+                    drag_term,
+                    orbit_0,
+                    p1,
+                    a0,
+                    atmospheric_s,
+                    c1,
+                    b0,
+                    c4,
+                    k0,
+                    k1,
+                    k14,
+                    p2,
+                    p14,
+                    p15,
+                ))
+            }
+        }
+    }
+
+    /// Initializes a new propagator from an `Elements` object
+    ///
+    /// This is the recommended method to initialize a propagator from a TLE or OMM.
+    /// The WGS84 model, the IAU sidereal time expression and the accurate UTC to J2000 expression are used.
+    ///
+    /// # Arguments
+    ///
+    /// * `elements` - Orbital elements and drag term parsed from a TLE or OMM
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn from_elements(elements: &Elements) -> Result<Self> {
+        Constants::new(
+            &WGS84,
+            iau_epoch_to_sidereal_time,
+            elements.epoch(),
+            elements.drag_term,
+            Orbit::from_kozai_elements(
+                &WGS84,
+                elements.inclination * (std::f64::consts::PI / 180.0),
+                elements.right_ascension * (std::f64::consts::PI / 180.0),
+                elements.eccentricity,
+                elements.argument_of_perigee * (std::f64::consts::PI / 180.0),
+                elements.mean_anomaly * (std::f64::consts::PI / 180.0),
+                elements.mean_motion * (std::f64::consts::PI / 720.0),
+            )?,
+        )
+    }
+
+    /// Initializes a new propagator from an `Elements` object
+    ///
+    /// This method should be used if compatibility with the AFSPC implementation is needed.
+    /// The WGS72 model, the AFSPC sidereal time expression and the AFSPC UTC to J2000 expression are used.
+    /// Like the AFSPC implementation, elements whose perigee is below the Earth's surface are accepted.
+    ///
+    /// # Arguments
+    ///
+    /// * `elements` - Orbital elements and drag term parsed from a TLE or OMM
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements_afspc_compatibility_mode(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn from_elements_afspc_compatibility_mode(elements: &Elements) -> Result<Self> {
+        Constants::new(
+            &WGS72,
+            afspc_epoch_to_sidereal_time,
+            elements.epoch_afspc_compatibility_mode(),
+            elements.drag_term,
+            Orbit::from_kozai_elements_without_perigee_check(
+                &WGS72,
+                elements.inclination * (std::f64::consts::PI / 180.0),
+                elements.right_ascension * (std::f64::consts::PI / 180.0),
+                elements.eccentricity,
+                elements.argument_of_perigee * (std::f64::consts::PI / 180.0),
+                elements.mean_anomaly * (std::f64::consts::PI / 180.0),
+                elements.mean_motion * (std::f64::consts::PI / 720.0),
+            )?,
+        )
+    }
+
+    /// Initializes a new propagator from Brouwer orbital elements and an explicit epoch
+    ///
+    /// This is equivalent to `Constants::new`, but the epoch is given as an `Epoch`
+    /// (constructed from a Julian date, a modified Julian date or a timestamp)
+    /// rather than as a number of years since J2000, and the sidereal time expression
+    /// is selected with a flag.
+    ///
+    /// # Arguments
+    ///
+    /// * `geopotential` - The model of Earth gravity to use in the conversion
+    /// * `orbit_0` - The Brouwer orbital elements at epoch
+    /// * `epoch` - The UTC epoch of the elements
+    /// * `drag_term` - The radiation pressure coefficient in earth radii⁻¹ (B*)
+    /// * `afspc_compatibility_mode` - Set to true to use the AFSPC sidereal time expression instead of the IAU one
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_orbit(
+    ///     &sgp4::WGS84,
+    ///     sgp4::Orbit::from_kozai_elements(
+    ///         &sgp4::WGS84,
+    ///         51.6461 * (std::f64::consts::PI / 180.0),
+    ///         221.2784 * (std::f64::consts::PI / 180.0),
+    ///         0.0001413,
+    ///         89.1723 * (std::f64::consts::PI / 180.0),
+    ///         280.4612 * (std::f64::consts::PI / 180.0),
+    ///         15.49507896 * (std::f64::consts::PI / 720.0),
+    ///     )?,
+    ///     sgp4::Epoch::from_julian_date(2459043.38612269),
+    ///     -0.31515e-4,
+    ///     false,
+    /// )?;
+    /// let prediction = constants.propagate(60.0)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn from_orbit(
+        geopotential: &'a Geopotential,
+        orbit_0: Orbit,
+        epoch: Epoch,
+        drag_term: f64,
+        afspc_compatibility_mode: bool,
+    ) -> Result<Self> {
+        if afspc_compatibility_mode {
+            Constants::new(
+                geopotential,
+                afspc_epoch_to_sidereal_time,
+                epoch.years_since_j2000,
+                drag_term,
+                orbit_0,
+            )
+        } else {
+            Constants::new(
+                geopotential,
+                iau_epoch_to_sidereal_time,
+                epoch.years_since_j2000,
+                drag_term,
+                orbit_0,
+            )
+        }
+    }
+
+    /// Fits SGP4 mean elements to a set of observed states
+    ///
+    /// This is the inverse of `Orbit::from_kozai_elements` followed by `Constants::propagate`.
+    /// The Brouwer elements at epoch are estimated with a least-squares differential correction
+    /// (Levenberg-Marquardt with a finite differences Jacobian) that drives the forward propagator.
+    /// The initial guess is the two-body osculating orbit of the first observation.
+    ///
+    /// A single state is enough to determine the six elements, but a short arc gives a better fit.
+    /// The drag term (B*) is not observable over short arcs and is set to zero.
+    /// The IAU sidereal time expression is used for resonant deep space orbits.
+    ///
+    /// # Arguments
+    ///
+    /// * `geopotential` - The model of Earth gravity to use in the fit
+    /// * `epoch` - The number of years since UTC 1 January 2000 12h00 (J2000)
+    /// * `states` - The observations, each given as the number of minutes since epoch,
+    ///   the position in km and the velocity in km.s⁻¹ (True Equator, Mean Equinox frame)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let constants = sgp4::Constants::from_elements(&elements)?;
+    /// let states = (0..10)
+    ///     .map(|index| {
+    ///         let t = index as f64 * 10.0;
+    ///         let prediction = constants.propagate(t)?;
+    ///         Ok((t, prediction.position, prediction.velocity))
+    ///     })
+    ///     .collect::<sgp4::Result<Vec<_>>>()?;
+    /// let fitted = sgp4::Constants::fit_from_observations(&sgp4::WGS84, elements.epoch(), &states)?;
+    /// let prediction = fitted.propagate(60.0)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn fit_from_observations(
+        geopotential: &'a Geopotential,
+        epoch: f64,
+        states: &[(f64, [f64; 3], [f64; 3])],
+    ) -> Result<Self> {
+        Constants::new(
+            geopotential,
+            iau_epoch_to_sidereal_time,
+            epoch,
+            0.0,
+            fit::fit_orbit(geopotential, epoch, states)?,
+        )
+    }
+
+    /// Returns a copy of this propagator with the drag term (B*) set to zero
+    ///
+    /// All the drag-dependent epoch quantities (C₁, C₄, k₀, k₁ and the high altitude drag terms)
+    /// are recalculated, which removes the secular drag decay. This is convenient to compare
+    /// drag-on and drag-off trajectories of the same element set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let drag_free_constants = constants.without_drag()?;
+    /// let with_drag = constants.propagate(60.0 * 24.0 * 7.0)?;
+    /// let without_drag = drag_free_constants.propagate(60.0 * 24.0 * 7.0)?;
+    /// println!("Δx = {} km", with_drag.position[0] - without_drag.position[0]);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn without_drag(&self) -> Result<Constants<'a>> {
+        self.with_drag_term_and_orbit(
+            0.0,
+            Orbit {
+                inclination: self.orbit_0.inclination,
+                right_ascension: self.orbit_0.right_ascension,
+                eccentricity: self.orbit_0.eccentricity,
+                argument_of_perigee: self.orbit_0.argument_of_perigee,
+                mean_anomaly: self.orbit_0.mean_anomaly,
+                mean_motion: self.orbit_0.mean_motion,
+            },
+        )
+    }
+
+    /// Returns a copy of this propagator with a user-supplied atmospheric parameter s
+    ///
+    /// Standard SGP4 derives s (and the density parameter (q₀ - s)⁴) from the perigee altitude
+    /// with fixed altitude bands (s = 78 km above the surface for perigees above 156 km).
+    /// This method replaces that value and recalculates the drag-dependent epoch quantities.
+    /// The result is no longer standard SGP4: it is meant for experimenting with alternate
+    /// atmosphere models, and predictions will not match other SGP4 implementations.
+    ///
+    /// # Arguments
+    ///
+    /// * `s` - The atmospheric parameter in earth radii, measured from the Earth center
+    ///   (s = 1 + altitude / aₑ). It must be smaller than the semi-major axis.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let experimental_constants = constants.with_atmospheric_s(1.0 + 90.0 / sgp4::WGS84.ae)?;
+    /// let prediction = experimental_constants.propagate(60.0 * 24.0)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn with_atmospheric_s(&self, s: f64) -> Result<Constants<'a>> {
+        self.rebuild(
+            self.drag_term,
+            Orbit {
+                inclination: self.orbit_0.inclination,
+                right_ascension: self.orbit_0.right_ascension,
+                eccentricity: self.orbit_0.eccentricity,
+                argument_of_perigee: self.orbit_0.argument_of_perigee,
+                mean_anomaly: self.orbit_0.mean_anomaly,
+                mean_motion: self.orbit_0.mean_motion,
+            },
+            Some(s),
+        )
+    }
+
+    /// Returns this propagator with or without the short-period corrections
+    ///
+    /// By default, SGP4 adds the short-period J₂ corrections to the radius, the argument of latitude,
+    /// the inclination, the right ascension and the velocity (the rₖ, uₖ, Iₖ and Ωₖ terms).
+    /// Disabling them returns the state calculated from the secular and long-period mean elements only,
+    /// which isolates the contribution of the short-period oscillations.
+    /// The result is no longer standard SGP4 and predictions will not match other SGP4 implementations.
+    ///
+    /// # Arguments
+    ///
+    /// * `short_period_corrections` - Whether the short-period corrections are applied
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let prediction = sgp4::Constants::from_elements(&elements)?.propagate(60.0)?;
+    /// let mean_prediction = sgp4::Constants::from_elements(&elements)?
+    ///     .with_short_period_corrections(false)
+    ///     .propagate(60.0)?;
+    /// let difference = (0..3)
+    ///     .map(|index| (prediction.position[index] - mean_prediction.position[index]).powi(2))
+    ///     .sum::<f64>()
+    ///     .sqrt();
+    /// assert!(difference > 0.1 && difference < 50.0);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn with_short_period_corrections(self, short_period_corrections: bool) -> Constants<'a> {
+        Constants {
+            short_period_corrections: short_period_corrections,
+            ..self
+        }
+    }
+
+    /// Returns this propagator with a different Kepler equation solver
+    ///
+    /// By default, SGP4 solves Kepler's equation with clamped Newton-Raphson iterations, as in the reference implementation.
+    /// The Laguerre-Conway solver converges in fewer iterations for highly eccentric orbits.
+    /// Both solvers stop when the correction is smaller than 10⁻¹² rad or after 10 iterations,
+    /// hence their predictions agree to a small fraction of a meter.
+    ///
+    /// # Arguments
+    ///
+    /// * `kepler_solver` - The method used to solve Kepler's equation
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("MOLNIYA 1-36".to_owned()),
+    ///         "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813".as_bytes(),
+    ///         "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656".as_bytes(),
+    ///     )?,
+    /// )?
+    /// .with_kepler_solver(sgp4::KeplerSolver::LaguerreConway);
+    /// let prediction = constants.propagate(60.0)?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn with_kepler_solver(self, kepler_solver: KeplerSolver) -> Constants<'a> {
+        Constants {
+            kepler_solver: kepler_solver,
+            ..self
+        }
+    }
+
+    /// Returns every quantity stored in the propagator
+    ///
+    /// The snapshot contains the epoch quantities (c₁, c₄, k₀, k₁, the secular rates...),
+    /// the method-specific coefficients (a₀", the high altitude drag terms, the third body perturbations
+    /// and the resonance coefficients) and the Brouwer elements at epoch. Only the quantities used
+    /// by the propagator's method are present. The keys are the names of the quantities
+    /// (for example "c1", "a0" or "inclination_0") and do not depend on the serialization format,
+    /// hence the snapshot can be serialized and compared across versions to detect numerical changes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("MOLNIYA 1-36".to_owned()),
+    ///         "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813".as_bytes(),
+    ///         "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let snapshot = constants.debug_dump();
+    /// assert_eq!(snapshot.method, "deep space, resonant (half day)");
+    /// println!("C₁ = {}", snapshot.values["c1"]);
+    /// println!("D₂₂₀₁ = {}", snapshot.values["d2201"]);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn debug_dump(&self) -> ConstantsSnapshot {
+        let atmospheric_parameters = self.atmospheric_parameters();
+        let mut values = [
+            ("epoch", self.epoch),
+            ("sidereal_time_0", self.sidereal_time_0),
+            ("drag_term", self.drag_term),
+            ("right_ascension_dot", self.right_ascension_dot),
+            ("argument_of_perigee_dot", self.argument_of_perigee_dot),
+            ("mean_anomaly_dot", self.mean_anomaly_dot),
+            ("c1", self.c1),
+            ("c4", self.c4),
+            ("k0", self.k0),
+            ("k1", self.k1),
+            ("s", atmospheric_parameters.s),
+            ("q0_minus_s_pow4", atmospheric_parameters.q0_minus_s_pow4),
+            ("a0", self.initial_semi_major_axis()),
+            ("inclination_0", self.orbit_0.inclination),
+            ("right_ascension_0", self.orbit_0.right_ascension),
+            ("eccentricity_0", self.orbit_0.eccentricity),
+            ("argument_of_perigee_0", self.orbit_0.argument_of_perigee),
+            ("mean_anomaly_0", self.orbit_0.mean_anomaly),
+            ("mean_motion_0", self.orbit_0.mean_motion),
+        ]
+        .iter()
+        .map(|(name, value)| ((*name).to_owned(), *value))
+        .collect::<std::collections::BTreeMap<String, f64>>();
+        let mut insert = |names_and_values: &[(&str, f64)]| {
+            for (name, value) in names_and_values {
+                values.insert((*name).to_owned(), *value);
+            }
+        };
+        match &self.method {
+            propagator::Method::NearEarth {
+                k2,
+                k3,
+                k4,
+                k5,
+                k6,
+                high_altitude,
+                ..
+            } => {
+                insert(&[
+                    ("k2", *k2),
+                    ("k3", *k3),
+                    ("k4", *k4),
+                    ("k5", *k5),
+                    ("k6", *k6),
+                ]);
+                if let propagator::HighAltitude::Yes {
+                    c5,
+                    d2,
+                    d3,
+                    d4,
+                    eta,
+                    k7,
+                    k8,
+                    k9,
+                    k10,
+                    elliptic,
+                } = high_altitude
+                {
+                    insert(&[
+                        ("c5", *c5),
+                        ("d2", *d2),
+                        ("d3", *d3),
+                        ("d4", *d4),
+                        ("eta", *eta),
+                        ("k7", *k7),
+                        ("k8", *k8),
+                        ("k9", *k9),
+                        ("k10", *k10),
+                    ]);
+                    if let propagator::Elliptic::Yes { k11, k12, k13 } = elliptic {
+                        insert(&[("k11", *k11), ("k12", *k12), ("k13", *k13)]);
+                    }
+                }
+            }
+            propagator::Method::DeepSpace {
+                eccentricity_dot,
+                inclination_dot,
+                solar_perturbations,
+                lunar_perturbations,
+                resonant,
+            } => {
+                insert(&[
+                    ("eccentricity_dot", *eccentricity_dot),
+                    ("inclination_dot", *inclination_dot),
+                ]);
+                if let propagator::Resonant::Yes {
+                    lambda_0,
+                    lambda_dot_0,
+                    resonance,
+                    ..
+                } = resonant
+                {
+                    insert(&[("lambda_0", *lambda_0), ("lambda_dot_0", *lambda_dot_0)]);
+                    match resonance {
+                        propagator::Resonance::OneDay { dr1, dr2, dr3 } => {
+                            insert(&[("dr1", *dr1), ("dr2", *dr2), ("dr3", *dr3)]);
+                        }
+                        propagator::Resonance::HalfDay {
+                            d2201,
+                            d2211,
+                            d3210,
+                            d3222,
+                            d4410,
+                            d4422,
+                            d5220,
+                            d5232,
+                            d5421,
+                            d5433,
+                            k14,
+                        } => insert(&[
+                            ("d2201", *d2201),
+                            ("d2211", *d2211),
+                            ("d3210", *d3210),
+                            ("d3222", *d3222),
+                            ("d4410", *d4410),
+                            ("d4422", *d4422),
+                            ("d5220", *d5220),
+                            ("d5232", *d5232),
+                            ("d5421", *d5421),
+                            ("d5433", *d5433),
+                            ("k14", *k14),
+                        ]),
+                    }
+                }
+                solar_perturbations.insert_values("solar", &mut values);
+                lunar_perturbations.insert_values("lunar", &mut values);
+            }
+        }
+        ConstantsSnapshot {
+            method: match &self.method {
+                propagator::Method::NearEarth { high_altitude, .. } => match high_altitude {
+                    propagator::HighAltitude::No {} => "near earth, simplified drag",
+                    propagator::HighAltitude::Yes { elliptic, .. } => match elliptic {
+                        propagator::Elliptic::No {} => "near earth, full drag, circular",
+                        propagator::Elliptic::Yes { .. } => "near earth, full drag, elliptic",
+                    },
+                },
+                propagator::Method::DeepSpace { resonant, .. } => match resonant {
+                    propagator::Resonant::No { .. } => "deep space, non-resonant",
+                    propagator::Resonant::Yes { resonance, .. } => match resonance {
+                        propagator::Resonance::OneDay { .. } => "deep space, resonant (one day)",
+                        propagator::Resonance::HalfDay { .. } => "deep space, resonant (half day)",
+                    },
+                },
+            }
+            .to_owned(),
+            values: values,
+        }
+    }
+
+    /// Returns the initial deep space resonance integrator state
+    ///
+    /// For most orbits, SGP4 propagation is stateless.
+    /// That is, predictions at different times are always calculated from the epoch quantities.
+    /// No calculations are saved by propagating to successive times sequentially.
+    ///
+    /// However, resonant deep space orbits (geosynchronous or Molniya) use an integrator
+    /// to estimate the resonance effects of Earth gravity, with a 720 min time step. If the propagation
+    /// times are monotonic, a few operations per prediction can be saved by re-using the integrator state.
+    ///
+    /// The high-level API `Constants::propagate` re-initializes the state with each propagation for simplicity.
+    /// `Constants::initial_state` and `Constants::propagate_from_state` can be used together
+    /// to speed up resonant deep space satellite propagation.
+    /// For non-deep space or non-resonant orbits, their behavior is identical to `Constants::propagate`.
+    ///
+    /// See `Constants::propagate_from_state` for an example.
+    pub fn initial_state(&self) -> Option<ResonanceState> {
+        match &self.method {
+            propagator::Method::NearEarth { .. } => None,
+            propagator::Method::DeepSpace { resonant, .. } => match resonant {
+                propagator::Resonant::No { .. } => None,
+                propagator::Resonant::Yes { lambda_0, .. } => {
+                    Some(ResonanceState::new(self.orbit_0.mean_motion, *lambda_0))
+                }
+            },
+        }
+    }
+
+    /// Returns true if the propagator uses the one-day (geosynchronous) resonance model
+    ///
+    /// Deep space orbits with a Brouwer mean motion n₀" in the range ]0.0034906585, 0.0052359877[ rad.min⁻¹
+    /// are integrated with the geosynchronous resonance terms.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         None,
+    ///         "1 28626U 05008A   06176.46683397 -.00000205  00000-0  10000-3 0  2190".as_bytes(),
+    ///         "2 28626   0.0019 286.9433 0000335  13.7918  55.6504  1.00270176  4891".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// assert!(constants.is_geosynchronous_resonant());
+    /// assert!(!constants.is_half_day_resonant());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn is_geosynchronous_resonant(&self) -> bool {
+        matches!(
+            self.method,
+            propagator::Method::DeepSpace {
+                resonant: propagator::Resonant::Yes {
+                    resonance: propagator::Resonance::OneDay { .. },
+                    ..
+                },
+                ..
+            }
+        )
+    }
+
+    /// Returns true if the propagator uses the half-day (Molniya) resonance model
+    ///
+    /// Deep space orbits with a Brouwer mean motion n₀" in the range [8.26 × 10⁻³, 9.24 × 10⁻³] rad.min⁻¹
+    /// and an eccentricity e₀ ≥ 0.5 are integrated with the half-day resonance terms.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("MOLNIYA 1-36".to_owned()),
+    ///         "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813".as_bytes(),
+    ///         "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// assert!(constants.is_half_day_resonant());
+    /// assert!(!constants.is_geosynchronous_resonant());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn is_half_day_resonant(&self) -> bool {
+        matches!(
+            self.method,
+            propagator::Method::DeepSpace {
+                resonant: propagator::Resonant::Yes {
+                    resonance: propagator::Resonance::HalfDay { .. },
+                    ..
+                },
+                ..
+            }
+        )
+    }
+
+    /// Returns the code path used by the propagator
+    ///
+    /// The method is selected at initialization from the Brouwer mean motion and eccentricity at epoch,
+    /// see `Constants::is_geosynchronous_resonant` and `Constants::is_half_day_resonant`
+    /// for the resonance bands.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// assert_eq!(constants.propagation_method(), sgp4::PropagationMethod::NearEarth);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagation_method(&self) -> PropagationMethod {
+        match &self.method {
+            propagator::Method::NearEarth { .. } => PropagationMethod::NearEarth,
+            propagator::Method::DeepSpace { resonant, .. } => match resonant {
+                propagator::Resonant::No { .. } => PropagationMethod::DeepSpaceNonResonant,
+                propagator::Resonant::Yes { resonance, .. } => match resonance {
+                    propagator::Resonance::OneDay { .. } => PropagationMethod::DeepSpaceOneDay,
+                    propagator::Resonance::HalfDay { .. } => PropagationMethod::DeepSpaceHalfDay,
+                },
+            },
+        }
+    }
+
+    /// Returns the atmospheric density parameters calculated at initialization
+    ///
+    /// By default, s is derived from the perigee altitude p₄:
+    /// p₅ = 20 km below 98 km, p₄ - 78 km below 156 km and 78 km otherwise,
+    /// s = p₅ / aₑ + 1 and (q₀ - s)⁴ = ((120 - p₅) / aₑ)⁴.
+    /// `Constants::with_atmospheric_s` replaces s, and (q₀ - s)⁴ is then calculated from it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let parameters = constants.atmospheric_parameters();
+    /// assert_eq!(parameters.s, 78.0 / sgp4::WGS84.ae + 1.0);
+    /// assert_eq!(parameters.q0_minus_s_pow4, (42.0 / sgp4::WGS84.ae).powi(4));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn atmospheric_parameters(&self) -> AtmosphericParameters {
+        let (s, p6) = Constants::atmospheric_s_and_p6(
+            self.geopotential,
+            // p₃ = a₀" (1 - e₀)
+            self.initial_semi_major_axis() * (1.0 - self.orbit_0.eccentricity),
+            self.atmospheric_s,
+        );
+        AtmosphericParameters {
+            s: s,
+            q0_minus_s_pow4: p6,
+        }
+    }
+
+    /// Returns the branches taken by the deep space mean elements calculation at the given time
+    ///
+    /// Deep space orbits switch to the Lyddane formulation of Ω and ω when the perturbed inclination
+    /// is smaller than 0.2 rad, and clamp the mean eccentricity to 10⁻⁶ before the solar and lunar periodics are added.
+    /// Both branches change the predictions noticeably and are a common source of discrepancies between implementations.
+    /// The branches are those of `Constants::propagate`.
+    /// `Constants::propagate_afspc_compatibility_mode` takes the same branches
+    /// but uses a different expression of ω in the Lyddane formulation.
+    ///
+    /// Returns `None` if the propagator uses the near earth model (orbital period smaller than 225 minutes).
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         None,
+    ///         "1 33335U 05008A   06176.46683397 -.00000205  00000-0  10000-3 0  2193".as_bytes(),
+    ///         "2 33335   0.0019 286.9433 0000004  13.7918  55.6504  1.00270176  4897".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let diagnostics = constants.deep_space_diagnostics(0.0)?.unwrap();
+    /// assert!(diagnostics.lyddane);
+    /// assert!(diagnostics.clamped_eccentricity);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn deep_space_diagnostics(&self, t: f64) -> Result<Option<DeepSpaceDiagnostics>> {
+        match self.method {
+            propagator::Method::NearEarth { .. } => Ok(None),
+            propagator::Method::DeepSpace { .. } => {
+                let mut warnings = Vec::new();
+                let (orbit, ..) = self.orbital_elements(
+                    t,
+                    self.initial_state().as_mut(),
+                    false,
+                    Some(&mut warnings),
+                )?;
+                Ok(Some(DeepSpaceDiagnostics {
+                    inclination: orbit.inclination,
+
+                    // I < 0.2
+                    lyddane: orbit.inclination < 0.2,
+                    clamped_eccentricity: warnings
+                        .iter()
+                        .any(|warning| matches!(warning, Warning::ClampedEccentricity { .. })),
+                }))
+            }
+        }
+    }
+
+    /// Returns the perturbations modelled by the propagator
+    ///
+    /// The J₂, J₃ and J₄ zonal harmonics are always modelled. Drag is modelled if B* ≠ 0,
+    /// the solar and lunar perturbations are modelled for deep space orbits
+    /// (orbital period of at least 225 minutes), and the resonance model is reported
+    /// by `Constants::is_geosynchronous_resonant` and `Constants::is_half_day_resonant`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("MOLNIYA 1-36".to_owned()),
+    ///         "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813".as_bytes(),
+    ///         "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// assert_eq!(
+    ///     constants.perturbation_profile(),
+    ///     sgp4::PerturbationProfile {
+    ///         drag: true,
+    ///         third_body: true,
+    ///         resonance: sgp4::ResonanceKind::HalfDay,
+    ///     },
+    /// );
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn perturbation_profile(&self) -> PerturbationProfile {
+        PerturbationProfile {
+            drag: self.drag_term != 0.0,
+            third_body: matches!(self.method, propagator::Method::DeepSpace { .. }),
+            resonance: if self.is_geosynchronous_resonant() {
+                ResonanceKind::OneDay
+            } else if self.is_half_day_resonant() {
+                ResonanceKind::HalfDay
+            } else {
+                ResonanceKind::None
+            },
+        }
+    }
+
+    /// Returns whether the near earth propagator uses the simplified drag model
+    ///
+    /// Near earth orbits with a perigee p₃ < 220 km / aₑ + 1 earth radii use a simplified drag model
+    /// that truncates the secular drag effects after the C₁ term (the D₂, D₃, D₄ and C₅ terms are omitted).
+    /// Other implementations may use a slightly different threshold.
+    /// Returns `None` for deep space propagators, which do not make this distinction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// assert_eq!(constants.uses_simplified_drag(), Some(false));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn uses_simplified_drag(&self) -> Option<bool> {
+        match &self.method {
+            propagator::Method::NearEarth { high_altitude, .. } => {
+                Some(matches!(high_altitude, propagator::HighAltitude::No { .. }))
+            }
+            propagator::Method::DeepSpace { .. } => None,
+        }
+    }
+
+    /// Returns a conservative range of propagation times in minutes since epoch
+    ///
+    /// SGP4 predictions degrade as the propagation time grows, mostly because the drag model
+    /// and the mean element theory are truncated. The range is symmetric about the epoch
+    /// and depends on `Constants::orbit_class`: ±14 days for low Earth and highly elliptical orbits,
+    /// ±30 days for medium Earth orbits, and ±60 days for geosynchronous and higher orbits.
+    /// Predictions outside this range are still calculated, but `Constants::propagate_checked`
+    /// reports them with `Warning::OutsideRecommendedTimeRange`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// assert_eq!(
+    ///     constants.recommended_time_range(),
+    ///     (-14.0 * 24.0 * 60.0, 14.0 * 24.0 * 60.0),
+    /// );
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn recommended_time_range(&self) -> (f64, f64) {
+        let days = match self.orbit_class() {
+            OrbitClass::Leo | OrbitClass::Heo => 14.0,
+            OrbitClass::Meo => 30.0,
+            OrbitClass::Geo | OrbitClass::BeyondGeo => 60.0,
+        };
+        (-days * (24.0 * 60.0), days * (24.0 * 60.0))
+    }
+
+    /// Returns the orbit regime at epoch
+    ///
+    /// The classification uses the Brouwer semi-major axis a₀" and the eccentricity e₀ at epoch:
+    /// - `OrbitClass::Heo` if e₀ ≥ `HEO_MIN_ECCENTRICITY`
+    /// - `OrbitClass::Leo` if a₀" - aₑ < `LEO_MAX_ALTITUDE`
+    /// - `OrbitClass::Geo` if |a₀" - `GEO_SEMI_MAJOR_AXIS`| ≤ `GEO_SEMI_MAJOR_AXIS_TOLERANCE`
+    /// - `OrbitClass::Meo` if a₀" is between the low Earth and geosynchronous bounds
+    /// - `OrbitClass::BeyondGeo` otherwise
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// assert_eq!(constants.orbit_class(), sgp4::OrbitClass::Leo);
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("MOLNIYA 1-36".to_owned()),
+    ///         "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813".as_bytes(),
+    ///         "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// assert_eq!(constants.orbit_class(), sgp4::OrbitClass::Heo);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn orbit_class(&self) -> OrbitClass {
+        if self.orbit_0.eccentricity >= HEO_MIN_ECCENTRICITY {
+            OrbitClass::Heo
+        } else {
+            let semi_major_axis = self.initial_semi_major_axis_km();
+            if semi_major_axis - self.geopotential.ae < LEO_MAX_ALTITUDE {
+                OrbitClass::Leo
+            } else if semi_major_axis < GEO_SEMI_MAJOR_AXIS - GEO_SEMI_MAJOR_AXIS_TOLERANCE {
+                OrbitClass::Meo
+            } else if semi_major_axis <= GEO_SEMI_MAJOR_AXIS + GEO_SEMI_MAJOR_AXIS_TOLERANCE {
+                OrbitClass::Geo
+            } else {
+                OrbitClass::BeyondGeo
+            }
+        }
+    }
+
+    /// Returns the number of revolutions completed between epoch and the given time
+    ///
+    /// The revolutions are counted with the mean argument of latitude M + ω, which includes
+    /// the secular drag effects on the mean motion and, for deep space orbits,
+    /// the resonance and third-body effects. The mean elements are sampled eight times per orbit.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration_minutes` - The number of minutes since epoch (can be positive, negative or zero),
+    ///   it must be finite and span at most `MAX_SAMPLES` samples
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let revolutions = constants.orbit_count(60.0 * 24.0)?;
+    /// assert!((revolutions - 15.5).abs() < 0.1);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn orbit_count(&self, duration_minutes: f64) -> Result<f64> {
+        let mut state = self.initial_state();
+
+        // Δt = │ 2π / (8 n₀") │
+        let step = std::f64::consts::PI / (4.0 * self.orbit_0.mean_motion);
+        let steps = propagator::step_count(duration_minutes.abs(), step)?;
+        let mut previous = self.orbit_0.mean_anomaly + self.orbit_0.argument_of_perigee;
+        let mut angle = 0.0;
+        for index in 1..=steps {
+            let t = duration_minutes * (index as f64 / steps as f64);
+            let (orbit, ..) = self.orbital_elements(t, state.as_mut(), false, None)?;
+            let current = orbit.mean_anomaly + orbit.argument_of_perigee;
+
+            // the increments are smaller than π in magnitude
+            angle += (current - previous + std::f64::consts::PI)
+                .rem_euclid(2.0 * std::f64::consts::PI)
+                - std::f64::consts::PI;
+            previous = current;
+        }
+        Ok(angle / (2.0 * std::f64::consts::PI))
+    }
+
+    /// Calculates the SGP4 position and velocity predictions at the start of a revolution
+    ///
+    /// Revolutions start when the mean argument of latitude M + ω crosses a multiple of 2π
+    /// (the mean ascending node), consistently with the revolution numbers of TLEs.
+    /// `rev` is counted relative to the element set's revolution number:
+    /// revolution 0 starts at the last node crossing before epoch,
+    /// and the absolute revolution number of revolution `rev` is `elements.revolution_number + rev`.
+    /// The crossing time is found by bisection of `Constants::orbit_count` to 10⁻⁶ min.
+    ///
+    /// # Arguments
+    ///
+    /// * `rev` - The revolution number relative to the element set's revolution number
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let constants = sgp4::Constants::from_elements(&elements)?;
+    /// for rev in 1..4 {
+    ///     let prediction = constants.propagate_at_revolution(rev)?;
+    ///     println!(
+    ///         "revolution {}: r = {:?} km",
+    ///         elements.revolution_number + rev as u64,
+    ///         prediction.position
+    ///     );
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_at_revolution(&self, rev: u32) -> Result<Prediction> {
+        let argument_of_latitude_0 = self.orbit_0.mean_anomaly + self.orbit_0.argument_of_perigee;
+
+        // number of revolutions between epoch and the start of the revolution
+        let target = ((argument_of_latitude_0 / (2.0 * std::f64::consts::PI)).floor() + rev as f64)
+            - argument_of_latitude_0 / (2.0 * std::f64::consts::PI);
+        let period = 2.0 * std::f64::consts::PI / self.orbit_0.mean_motion;
+        let mut low = target * period - period;
+        let mut high = target * period + period;
+        for _ in 0..100 {
+            if self.orbit_count(low)? <= target {
+                break;
+            }
+            low -= period;
+        }
+        for _ in 0..100 {
+            if self.orbit_count(high)? >= target {
+                break;
+            }
+            high += period;
+        }
+        if self.orbit_count(low)? > target || self.orbit_count(high)? < target {
+            return Err(Error::new(
+                "the revolution could not be bracketed".to_owned(),
+            ));
+        }
+        while high - low > 1.0e-6 {
+            let middle = 0.5 * (low + high);
+            if self.orbit_count(middle)? < target {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        self.propagate(0.5 * (low + high))
+    }
+
+    /// Returns the east-west drift rate of the sub-satellite longitude in deg.day⁻¹
+    ///
+    /// The drift rate is the difference between the secular rate of the mean longitude
+    /// Ω̇ + ω̇ + Ṁ (including the third-body effects) and the Earth's sidereal rotation rate.
+    /// A positive value corresponds to an eastward drift.
+    /// It returns `None` if the orbit is not geosynchronous (see `Constants::orbit_class`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         None,
+    ///         "1 25954U 99060A   04039.68057285 -.00000108  00000-0  00000-0 0  6847".as_bytes(),
+    ///         "2 25954   0.0004 243.8136 0001765  15.5294  22.7134  1.00271289 15615".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let drift = constants.geo_longitude_drift().unwrap();
+    /// assert!(drift.abs() < 1.0);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn geo_longitude_drift(&self) -> Option<f64> {
+        if self.orbit_class() == OrbitClass::Geo {
+            // λ̇ = Ω̇ + ω̇ + Ṁ - θ̇
+            Some(
+                (self.right_ascension_dot + self.argument_of_perigee_dot + self.mean_anomaly_dot
+                    - deep_space::SIDEREAL_SPEED)
+                    * (180.0 / std::f64::consts::PI)
+                    * (24.0 * 60.0),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Returns the sidereal time at epoch in rad
+    ///
+    /// This is the value calculated by the sidereal time expression given to the propagator
+    /// (IAU with `Constants::from_elements`, AFSPC with `Constants::from_elements_afspc_compatibility_mode`).
+    /// It is stored by every propagator and used by the deep space resonance terms and by `Constants::propagate_frames`,
+    /// hence it can be used to build Earth-fixed conversions consistent with the propagator,
+    /// with θ = θ₀ + θ̇ t and `teme_to_ecef_matrix`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let constants = sgp4::Constants::from_elements(&elements)?;
+    /// assert_eq!(
+    ///     constants.epoch_sidereal_time(),
+    ///     sgp4::iau_epoch_to_sidereal_time(elements.epoch()),
+    /// );
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn epoch_sidereal_time(&self) -> f64 {
+        self.sidereal_time_0
+    }
+
+    /// Returns the geographic longitude of the ascending node at epoch in rad
+    ///
+    /// The longitude λ = Ω₀ - θ₀ is the Brouwer right ascension of the ascending node at epoch
+    /// minus the sidereal time at epoch (see `Constants::epoch_sidereal_time`), in the range ]-π, π].
+    /// It is a phase reference for ground tracks: the satellite does not necessarily
+    /// cross the equator at epoch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let longitude = constants.ascending_node_longitude();
+    /// assert!(longitude.abs() <= std::f64::consts::PI);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn ascending_node_longitude(&self) -> f64 {
+        // λ = Ω₀ - θ₀
+        let longitude = self.orbit_0.right_ascension - self.sidereal_time_0;
+        longitude.sin().atan2(longitude.cos())
+    }
+
+    /// Returns the secular rates of the right ascension, argument of perigee and mean anomaly
+    ///
+    /// The rates are the ones used during propagation. Near earth orbits include the J₂ and J₄
+    /// secular effects, and deep space orbits also include the solar and lunar secular effects.
+    /// The secular drag effects, which are not linear in time, are not included.
+    ///
+    /// ω̇ vanishes near the critical inclination (cos²I = ¹/₅, about 63.4°). Unlike Brouwer's theory,
+    /// SGP4 omits the long-period terms proportional to 1 / (1 - 5 cos²I), hence the predictions
+    /// remain smooth functions of the inclination at and around the critical inclination.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let rates = constants.secular_rates();
+    /// println!(
+    ///     "Ω̇ = {} deg.day⁻¹",
+    ///     rates.right_ascension_dot * (180.0 / std::f64::consts::PI) * (24.0 * 60.0)
+    /// );
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn secular_rates(&self) -> SecularRates {
+        SecularRates {
+            right_ascension_dot: self.right_ascension_dot,
+            argument_of_perigee_dot: self.argument_of_perigee_dot,
+            mean_anomaly_dot: self.mean_anomaly_dot,
+        }
+    }
+
+    /// Returns the secular drift of the orbital elements over a duration
+    ///
+    /// The drifts are the secular rates multiplied by the duration, without propagating.
+    /// Near earth orbits include the J₂ and J₄ secular effects on Ω, ω and M.
+    /// Deep space orbits also include the solar and lunar secular effects on Ω, ω and M,
+    /// and on e and I. The drag effects and the periodic effects
+    /// (see `Constants::third_body_periodic_effects`) are not included.
+    /// The angles are not wrapped, so that multiple revolutions can be counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - The duration of the report in minutes (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let drift = constants.elements_drift_report(90.0 * 24.0 * 60.0);
+    ///
+    /// // the ISS orbit plane regresses by about 5° per day
+    /// let right_ascension_drift = drift.right_ascension * (180.0 / std::f64::consts::PI);
+    /// assert!(right_ascension_drift < -400.0 && right_ascension_drift > -500.0);
+    /// assert_eq!(drift.eccentricity, 0.0);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn elements_drift_report(&self, duration: f64) -> ElementsDrift {
+        let (eccentricity_dot, inclination_dot) = match &self.method {
+            propagator::Method::NearEarth { .. } => (0.0, 0.0),
+            propagator::Method::DeepSpace {
+                eccentricity_dot,
+                inclination_dot,
+                ..
+            } => (*eccentricity_dot, *inclination_dot),
+        };
+        ElementsDrift {
+            duration: duration,
+            right_ascension: self.right_ascension_dot * duration,
+            argument_of_perigee: self.argument_of_perigee_dot * duration,
+            mean_anomaly: self.mean_anomaly_dot * duration,
+            eccentricity: eccentricity_dot * duration,
+            inclination: inclination_dot * duration,
+        }
+    }
+
+    /// Returns the Brouwer mean semi-major axis at epoch in earth radii
+    ///
+    /// a₀" = (kₑ / n₀")²ᐟ³ is the value used to initialize the propagator,
+    /// see `Constants::initial_semi_major_axis_km` for the same quantity in km.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// assert!((constants.initial_semi_major_axis() - 1.0657).abs() < 1.0e-4);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn initial_semi_major_axis(&self) -> f64 {
+        self.orbit_0.semi_major_axis(self.geopotential)
+    }
+
+    /// Returns the Brouwer mean semi-major axis at epoch in km
+    ///
+    /// It is equal to `Constants::initial_semi_major_axis` multiplied by the equatorial radius aₑ.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// println!("a₀\" = {} km", constants.initial_semi_major_axis_km());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn initial_semi_major_axis_km(&self) -> f64 {
+        self.initial_semi_major_axis() * self.geopotential.ae
+    }
+
+    /// Returns the Brouwer mean orbital elements at epoch
+    ///
+    /// The semi-major axis a₀" = (kₑ / n₀")²ᐟ³ aₑ is derived from the Brouwer mean motion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let mean_elements = constants.mean_elements();
+    /// println!("a₀\" = {} km", mean_elements.semi_major_axis);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn mean_elements(&self) -> MeanElements {
+        MeanElements {
+            semi_major_axis: self.initial_semi_major_axis_km(),
+            eccentricity: self.orbit_0.eccentricity,
+            inclination: self.orbit_0.inclination,
+            right_ascension: self.orbit_0.right_ascension,
+            argument_of_perigee: self.orbit_0.argument_of_perigee,
+            mean_anomaly: self.orbit_0.mean_anomaly,
+        }
+    }
+
+    /// Returns the mean eccentricity and anomalies at the given time
+    ///
+    /// The mean elements include the secular and long-period effects but not the short-period corrections.
+    /// The true anomaly is calculated with `kepler_solve` and `eccentric_to_true`.
+    /// If the mean eccentricity is clamped to 10⁻⁶ (see `Warning::ClampedEccentricity`), the perigee is not defined:
+    /// `Anomalies::circular` is true and the anomalies are replaced with arguments of latitude.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let anomalies = constants.anomalies(60.0)?;
+    /// assert!(!anomalies.circular);
+    /// println!("ν = {} rad", anomalies.true_anomaly);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn anomalies(&self, t: f64) -> Result<Anomalies> {
+        let mut warnings = Vec::new();
+        let (orbit, ..) =
+            self.orbital_elements(t, self.initial_state().as_mut(), false, Some(&mut warnings))?;
+        let true_anomaly = model::eccentric_to_true(
+            model::kepler_solve(orbit.mean_anomaly, orbit.eccentricity),
+            orbit.eccentricity,
+        );
+        let circular = warnings
+            .iter()
+            .any(|warning| matches!(warning, Warning::ClampedEccentricity { .. }));
+        let argument_of_perigee = if circular {
+            0.0
+        } else {
+            orbit.argument_of_perigee
+        };
+        Ok(Anomalies {
+            eccentricity: orbit.eccentricity,
+            circular: circular,
+            argument_of_perigee: argument_of_perigee.rem_euclid(2.0 * std::f64::consts::PI),
+            mean_anomaly: (orbit.mean_anomaly + orbit.argument_of_perigee - argument_of_perigee)
+                .rem_euclid(2.0 * std::f64::consts::PI),
+            true_anomaly: (true_anomaly + orbit.argument_of_perigee - argument_of_perigee)
+                .rem_euclid(2.0 * std::f64::consts::PI),
+        })
+    }
+
+    /// Returns the mean motion at the given time in rev.day⁻¹
+    ///
+    /// This is the Brouwer mean motion n = kₑ / a³ᐟ² calculated from the mean semi-major axis a at `t`,
+    /// which decays with the secular drag effects (and, for resonant deep space orbits,
+    /// evolves with the resonance terms). It is converted from rad.min⁻¹ to the TLE unit.
+    /// Note that TLEs store the Kozai mean motion, which differs from the Brouwer mean motion
+    /// by a relative amount of the order of J₂ (see `Orbit::from_kozai_elements`).
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let change = constants.mean_motion_at(10.0 * 24.0 * 60.0)? - constants.mean_motion_at(0.0)?;
+    /// println!("Δn = {} rev.day⁻¹", change);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn mean_motion_at(&self, t: f64) -> Result<f64> {
+        let (orbit, ..) = self.orbital_elements(t, self.initial_state().as_mut(), false, None)?;
+        Ok(orbit.mean_motion * (24.0 * 60.0) / (2.0 * std::f64::consts::PI))
+    }
+
+    /// Returns the perigee distance from the Earth's center at epoch in km
+    ///
+    /// It is calculated from the Brouwer elements, r = a₀" (1 - e₀),
+    /// and does not include the short-period and drag variations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// println!("perigee altitude = {} km", constants.perigee_radius() - sgp4::WGS84.ae);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn perigee_radius(&self) -> f64 {
+        self.mean_elements().semi_major_axis * (1.0 - self.orbit_0.eccentricity)
+    }
+
+    /// Returns the apogee distance from the Earth's center at epoch in km
+    ///
+    /// It is calculated from the Brouwer elements, r = a₀" (1 + e₀),
+    /// and does not include the short-period and drag variations.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// println!("apogee altitude = {} km", constants.apogee_radius() - sgp4::WGS84.ae);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn apogee_radius(&self) -> f64 {
+        self.mean_elements().semi_major_axis * (1.0 + self.orbit_0.eccentricity)
+    }
+
+    /// Returns the osculating orbital period at the given time in minutes
+    ///
+    /// The osculating semi-major axis is calculated from the SGP4 prediction with the vis-viva equation,
+    /// a = (2 / r - v² / μ)⁻¹, and the period is 2π (a³ / μ)¹ᐟ².
+    /// Unlike the mean period, it includes the short-period oscillations of the perturbed orbit.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let period = constants.osculating_period(60.0)?;
+    /// assert!((period - 92.9).abs() < 0.5);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn osculating_period(&self, t: f64) -> Result<f64> {
+        let prediction = self.propagate(t)?;
+
+        // r and v in earth radii and earth radii.min⁻¹
+        let r = model::norm(&prediction.position) / self.geopotential.ae;
+        let v_squared = model::dot(&prediction.velocity, &prediction.velocity)
+            * (60.0 / self.geopotential.ae).powi(2);
+
+        // a⁻¹ = 2 / r - v² / kₑ²
+        let inverse_a = 2.0 / r - v_squared / self.geopotential.ke.powi(2);
+        if inverse_a.is_nan() || inverse_a <= 0.0 {
+            Err(Error::new(
+                "the osculating orbit is not elliptic".to_owned(),
+            ))
+        } else {
+            // P = 2π a³ᐟ² / kₑ
+            Ok(2.0 * std::f64::consts::PI * inverse_a.powf(-1.5) / self.geopotential.ke)
+        }
+    }
+
+    /// Calculates the SGP4 position and velocity predictions
+    ///
+    /// This is an advanced API which results in marginally faster propagation than `Constants::propagate` in some cases
+    /// (see `Constants::initial_state` for details), at the cost of added complexity for the user.
+    ///
+    /// The propagation times must be monotonic if the same resonance state is used repeatedly.
+    /// The `afspc_compatibility_mode` makes a difference only if the satellite is on a Lyddane deep space orbit
+    /// (period greater than 225 min and inclination smaller than 0.2 rad).
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    /// * `state` - The deep space propagator state returned by `Constants::initial_state`
+    /// * `afspc_compatibility_mode` - Set to true if compatibility with the AFSPC implementation is needed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("MOLNIYA 1-36".to_owned()),
+    ///     "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813".as_bytes(),
+    ///     "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656".as_bytes(),
+    /// )?;
+    /// let constants = sgp4::Constants::from_elements(&elements)?;
+    /// let mut state = constants.initial_state();
+    /// for days in 0..7 {
+    ///     println!("t = {} min", days * 60 * 24);
+    ///     let prediction =
+    ///         constants.propagate_from_state((days * 60 * 24) as f64, state.as_mut(), false)?;
+    ///     println!("    r = {:?} km", prediction.position);
+    ///     println!("    ṙ = {:?} km.s⁻¹", prediction.velocity);
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_from_state(
+        &self,
+        t: f64,
+        state: Option<&mut ResonanceState>,
+        afspc_compatibility_mode: bool,
+    ) -> Result<Prediction> {
+        self.propagate_from_state_with_derived(t, state, afspc_compatibility_mode, None, None)
+            .map(|(prediction, _)| prediction)
+    }
+
+    // calculates the mean orbital elements at t, with the secular and long-period effects
+    // and the quantities needed by the short-period corrections
+    // warnings, if provided, collects the non-fatal degradations of the mean elements
+    fn orbital_elements(
+        &self,
+        t: f64,
+        state: Option<&mut ResonanceState>,
+        afspc_compatibility_mode: bool,
+        warnings: Option<&mut Vec<Warning>>,
+    ) -> Result<(Orbit, f64, f64, f64, f64, f64, f64)> {
+        // p₂₂ = Ω₀ + Ω̇ t + k₀ t²
+        let p22 = self.orbit_0.right_ascension + self.right_ascension_dot * t + self.k0 * t.powi(2);
+
+        // p₂₃ = ω₀ + ω̇ t
+        let p23 = self.orbit_0.argument_of_perigee + self.argument_of_perigee_dot * t;
+        match &self.method {
+            propagator::Method::NearEarth {
+                a0,
+                k2,
+                k3,
+                k4,
+                k5,
+                k6,
+                high_altitude,
+            } => {
+                assert!(
+                    state.is_none(),
+                    "state must be None with a near-earth propagator",
+                );
+                self.near_earth_orbital_elements(
+                    *a0,
+                    *k2,
+                    *k3,
+                    *k4,
+                    *k5,
+                    *k6,
+                    high_altitude,
+                    t,
+                    p22,
+                    p23,
+                    warnings,
+                )
+            }
+            propagator::Method::DeepSpace {
+                eccentricity_dot,
+                inclination_dot,
+                solar_perturbations,
+                lunar_perturbations,
+                resonant,
+            } => self.deep_space_orbital_elements(
+                *eccentricity_dot,
+                *inclination_dot,
+                solar_perturbations,
+                lunar_perturbations,
+                resonant,
+                state,
+                t,
+                p22,
+                p23,
+                afspc_compatibility_mode,
+                warnings,
+            ),
+        }
+    }
+
+    // iterates, if provided, collects the successive (E + ω) values of the Kepler solver
+    // warnings, if provided, collects the non-fatal degradations of the prediction
+    fn propagate_from_state_with_derived(
+        &self,
+        t: f64,
+        state: Option<&mut ResonanceState>,
+        afspc_compatibility_mode: bool,
+        mut iterates: Option<&mut Vec<f64>>,
+        mut warnings: Option<&mut Vec<Warning>>,
+    ) -> Result<(Prediction, DerivedQuantities)> {
+        let (orbit, a, p32, p33, p34, p35, p36) =
+            self.orbital_elements(t, state, afspc_compatibility_mode, warnings.as_deref_mut())?;
+        if let Some(warnings) = warnings.as_mut() {
+            // |1 + cos I| ≤ 1.5 × 10⁻¹²
+            if (1.0 + orbit.inclination.cos()).abs() <= 1.5e-12 {
+                warnings.push(Warning::SingularInclination {
+                    inclination: orbit.inclination,
+                });
+            }
+        }
+
+        // p₃₇ = 1 / (a (1 - e²))
+        let p37 = 1.0 / (a * (1.0 - orbit.eccentricity.powi(2)));
+
+        // aₓₙ = e cos ω
+        let axn = orbit.eccentricity * orbit.argument_of_perigee.cos();
+
+        // aᵧₙ = e sin ω + p₃₇ p₃₂
+        let ayn = orbit.eccentricity * orbit.argument_of_perigee.sin() + p37 * p32;
+
+        // p₃₈ = M + ω + p₃₇ p₃₅ aₓₙ rem 2π
+        let p38 = (orbit.mean_anomaly + orbit.argument_of_perigee + p37 * p35 * axn)
+            % (2.0 * std::f64::consts::PI);
+
+        // (E + ω)₀ = p₃₈
+        let mut ew = p38;
+        if let Some(iterates) = iterates.as_mut() {
+            iterates.push(ew);
+        }
+        let mut converged = false;
+        for _ in 0..10 {
+            let delta = match self.kepler_solver {
+                //             p₃₈ - aᵧₙ cos (E + ω)ᵢ + aₓₙ sin (E + ω)ᵢ - (E + ω)ᵢ
+                // Δ(E + ω)ᵢ = ---------------------------------------------------
+                //                   1 - cos (E + ω)ᵢ aₓₙ - sin (E + ω)ᵢ aᵧₙ
+                KeplerSolver::NewtonRaphson => {
+                    (p38 - ayn * ew.cos() + axn * ew.sin() - ew)
+                        / (1.0 - ew.cos() * axn - ew.sin() * ayn)
+                }
+                KeplerSolver::LaguerreConway => {
+                    // f = (E + ω)ᵢ - aₓₙ sin (E + ω)ᵢ + aᵧₙ cos (E + ω)ᵢ - p₃₈
+                    let f = ew - axn * ew.sin() + ayn * ew.cos() - p38;
+
+                    // f' = 1 - cos (E + ω)ᵢ aₓₙ - sin (E + ω)ᵢ aᵧₙ
+                    let f_prime = 1.0 - ew.cos() * axn - ew.sin() * ayn;
+
+                    // f" = aₓₙ sin (E + ω)ᵢ - aᵧₙ cos (E + ω)ᵢ
+                    let f_second = axn * ew.sin() - ayn * ew.cos();
+
+                    //                                 - 5 f
+                    // Δ(E + ω)ᵢ = ----------------------------------------------
+                    //             f' ± |16 f'² - 20 f f"|¹ᐟ² (± has the sign of f')
+                    -5.0 * f
+                        / (f_prime
+                            + (16.0 * f_prime.powi(2) - 20.0 * f * f_second)
+                                .abs()
+                                .sqrt()
+                                .copysign(f_prime))
+                }
+            };
+
+            if delta.abs() < 1.0e-12 {
+                converged = true;
+                break;
+            }
+
+            // (E + ω)ᵢ₊₁ = (E + ω)ᵢ + Δ(E + ω)ᵢ|[-0.95, 0.95]
+            ew += delta.clamp(-0.95, 0.95);
+            if let Some(iterates) = iterates.as_mut() {
+                iterates.push(ew);
+            }
+        }
+        if !converged {
+            if let Some(warnings) = warnings.as_mut() {
+                warnings.push(Warning::KeplerNotConverged {
+                    // p₃₈ - aᵧₙ cos (E + ω) + aₓₙ sin (E + ω) - (E + ω)
+                    residual: p38 - ayn * ew.cos() + axn * ew.sin() - ew,
+                });
+            }
+        }
+
+        // p₃₉ = aₓₙ² + aᵧₙ²
+        let p39 = axn.powi(2) + ayn.powi(2);
+
+        // pₗ = a (1 - p₃₉)
+        let pl = a * (1.0 - p39);
+        if pl < 0.0 {
+            Err(Error::new("negative semi-latus rectum".to_owned()))
+        } else {
+            // p₄₀ = aₓₙ sin(E + ω) - aᵧₙ cos(E + ω)
+            let p40 = axn * ew.sin() - ayn * ew.cos();
+
+            // r = a (1 - aₓₙ cos(E + ω) + aᵧₙ sin(E + ω))
+            let r = a * (1.0 - (axn * ew.cos() + ayn * ew.sin()));
+
+            // ṙ = a¹ᐟ² p₄₀ / r
+            let r_dot = a.sqrt() * p40 / r;
+
+            // β = (1 - p₃₉)¹ᐟ²
+            let b = (1.0 - p39).sqrt();
+
+            // p₄₁ = p₄₀ / (1 + β)
+            let p41 = p40 / (1.0 + b);
+
+            // p₄₂ = a / r (sin(E + ω) - aᵧₙ - aₓₙ p₄₁)
+            let p42 = a / r * (ew.sin() - ayn - axn * p41);
+
+            // p₄₃ = a / r (cos(E + ω) - aₓₙ + aᵧₙ p₄₁)
+            let p43 = a / r * (ew.cos() - axn + ayn * p41);
+
+            //           p₄₂
+            // u = tan⁻¹ ---
+            //           p₄₃
+            let u = p42.atan2(p43);
+
+            // p₄₄ = 2 p₄₃ p₄₂
+            let p44 = 2.0 * p43 * p42;
+
+            // p₄₅ = 1 - 2 p₄₂²
+            let p45 = 1.0 - 2.0 * p42.powi(2);
+
+            // p₄₆ = (¹/₂ J₂ / pₗ) / pₗ
+            let p46 = 0.5 * self.geopotential.j2 / pl / pl;
+
+            let (rk, uk, inclination_k, right_ascension_k, rk_dot, rfk_dot) =
+                if self.short_period_corrections {
+                    // rₖ = r (1 - ³/₂ p₄₆ β p₃₆) + ¹/₂ (¹/₂ J₂ / pₗ) p₃₃ p₄₅
+                    let rk = r * (1.0 - 1.5 * p46 * b * p36)
+                        + 0.5 * (0.5 * self.geopotential.j2 / pl) * p33 * p45;
+
+                    // uₖ = u - ¹/₄ p₄₆ p₃₄ p₄₄
+                    let uk = u - 0.25 * p46 * p34 * p44;
+
+                    // Iₖ = I + ³/₂ p₄₆ cos I sin I p₄₅
+                    let inclination_k = orbit.inclination
+                        + 1.5 * p46 * orbit.inclination.cos() * orbit.inclination.sin() * p45;
+
+                    // Ωₖ = Ω + ³/₂ p₄₆ cos I p₄₄
+                    let right_ascension_k =
+                        orbit.right_ascension + 1.5 * p46 * orbit.inclination.cos() * p44;
+
+                    // ṙₖ = ṙ + n (¹/₂ J₂ / pₗ) p₃₃ / kₑ
+                    let rk_dot = r_dot
+                        - orbit.mean_motion * (0.5 * self.geopotential.j2 / pl) * p33 * p44
+                            / self.geopotential.ke;
+
+                    // rḟₖ = pₗ¹ᐟ² / r + n (¹/₂ J₂ / pₗ) (p₃₃ p₄₅ + ³/₂ p₃₆) / kₑ
+                    let rfk_dot = pl.sqrt() / r
+                        + orbit.mean_motion
+                            * (0.5 * self.geopotential.j2 / pl)
+                            * (p33 * p45 + 1.5 * p36)
+                            / self.geopotential.ke;
+                    (rk, uk, inclination_k, right_ascension_k, rk_dot, rfk_dot)
+                } else {
+                    // rₖ = r, uₖ = u, Iₖ = I, Ωₖ = Ω, ṙₖ = ṙ, rḟₖ = pₗ¹ᐟ² / r
+                    (
+                        r,
+                        u,
+                        orbit.inclination,
+                        orbit.right_ascension,
+                        r_dot,
+                        pl.sqrt() / r,
+                    )
+                };
+
+            // u₀ = - sin Ωₖ cos Iₖ sin uₖ + cos Ωₖ cos uₖ
+            let u0 = -right_ascension_k.sin() * inclination_k.cos() * uk.sin()
+                + right_ascension_k.cos() * uk.cos();
+            // u₁ = cos Ωₖ cos Iₖ sin uₖ + sin Ωₖ cos uₖ
+            let u1 = right_ascension_k.cos() * inclination_k.cos() * uk.sin()
+                + right_ascension_k.sin() * uk.cos();
+            // u₂ = sin Iₖ sin uₖ
+            let u2 = inclination_k.sin() * uk.sin();
+            if rk.is_nan() || rk * self.geopotential.ae > MAX_GEOCENTRIC_RADIUS {
+                return Err(Error::new(format!(
+                    "non-physical state with a geocentric radius of {} km",
+                    rk * self.geopotential.ae
+                )));
+            }
+            Ok((
+                Prediction {
+                    position: [
+                        // r₀ = rₖ u₀ aₑ
+                        rk * u0 * self.geopotential.ae,
+                        // r₁ = rₖ u₁ aₑ
+                        rk * u1 * self.geopotential.ae,
+                        // r₂ = rₖ u₂ aₑ
+                        rk * u2 * self.geopotential.ae,
+                    ],
+                    velocity: [
+                        // ṙ₀ = (ṙₖ u₀ + rḟₖ (- sin Ωₖ cos Iₖ cos uₖ - cos Ωₖ sin uₖ)) aₑ kₑ / 60
+                        (rk_dot * u0
+                            + rfk_dot
+                                * (-right_ascension_k.sin() * inclination_k.cos() * uk.cos()
+                                    - right_ascension_k.cos() * uk.sin()))
+                            * (self.geopotential.ae * self.geopotential.ke / 60.0),
+                        // ṙ₁ = (ṙₖ u₁ + rḟₖ (cos Ωₖ cos Iₖ cos uₖ - sin Ωₖ sin uₖ)) aₑ kₑ / 60
+                        (rk_dot * u1
+                            + rfk_dot
+                                * (right_ascension_k.cos() * inclination_k.cos() * uk.cos()
+                                    - right_ascension_k.sin() * uk.sin()))
+                            * (self.geopotential.ae * self.geopotential.ke / 60.0),
+                        // ṙ₂ = (ṙₖ u₂ + rḟₖ (sin Iₖ cos uₖ)) aₑ kₑ / 60
+                        (rk_dot * u2 + rfk_dot * (inclination_k.sin() * uk.cos()))
+                            * (self.geopotential.ae * self.geopotential.ke / 60.0),
+                    ],
+                },
+                DerivedQuantities {
+                    semi_latus_rectum: pl * self.geopotential.ae,
+
+                    // h = kₑ pₗ¹ᐟ² aₑ² / 60
+                    specific_angular_momentum: self.geopotential.ke
+                        * pl.sqrt()
+                        * (self.geopotential.ae.powi(2) / 60.0),
+                },
+            ))
+        }
+    }
+
+    /// Calculates the SGP4 position and velocity predictions
+    ///
+    /// This is the recommended method to propagate epoch orbital elements.
+    /// Each call integrates the deep space resonance from a fresh state, so successive calls
+    /// can use arbitrary times in either direction (see `Constants::propagate_from_state`
+    /// to reuse a state for monotonic times).
+    ///
+    /// Predictions are deterministic: the propagator does not use fused multiply-add (`mul_add`),
+    /// and Rust never contracts floating-point operations, hence the arithmetic is bit-identical
+    /// on every IEEE 754 platform and optimization level. The transcendental functions
+    /// (sin, cos, atan2, powf...) are provided by the platform's math library and may differ
+    /// in the last bit between platforms. The integration tests compare a reference vector bit for bit
+    /// on x86_64 Linux (glibc), and within 10⁻⁹ km and 10⁻¹² km.s⁻¹ on other platforms.
+    ///
+    /// # Arguments
+    /// `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements_afspc_compatibility_mode(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let prediction = constants.propagate(60.0 * 24.0);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate(&self, t: f64) -> Result<Prediction> {
+        self.propagate_from_state(t, self.initial_state().as_mut(), false)
+    }
+
+    /// Calculates the SGP4 position and velocity predictions in the TEME and Earth-fixed frames
+    ///
+    /// The Earth-fixed (pseudo ECEF) prediction is obtained by rotating the TEME prediction
+    /// about the polar axis by the sidereal time θ = θ₀ + θ̇ t, where θ₀ is the epoch sidereal time
+    /// calculated once when the constants are initialized. The Earth-fixed velocity includes the
+    /// Earth rotation term. Polar motion is neglected.
+    ///
+    /// # Arguments
+    /// `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let (teme, ecef) = constants.propagate_frames(60.0)?;
+    /// println!("TEME r = {:?} km", teme.position);
+    /// println!("ECEF r = {:?} km", ecef.position);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_frames(&self, t: f64) -> Result<(Prediction, Prediction)> {
+        let prediction = self.propagate(t)?;
+
+        // θ = θ₀ + θ̇ t
+        let sidereal_time = (self.sidereal_time_0 + deep_space::SIDEREAL_SPEED * t)
+            .rem_euclid(2.0 * std::f64::consts::PI);
+        let matrix = model::teme_to_ecef_matrix(sidereal_time);
+
+        // rₑ = R r
+        let position = [
+            matrix[0][0] * prediction.position[0] + matrix[0][1] * prediction.position[1],
+            matrix[1][0] * prediction.position[0] + matrix[1][1] * prediction.position[1],
+            prediction.position[2],
+        ];
+
+        // ṙₑ = R ṙ - ω × rₑ with ω = θ̇ / 60 in rad.s⁻¹
+        let velocity = [
+            matrix[0][0] * prediction.velocity[0]
+                + matrix[0][1] * prediction.velocity[1]
+                + deep_space::SIDEREAL_SPEED / 60.0 * position[1],
+            matrix[1][0] * prediction.velocity[0] + matrix[1][1] * prediction.velocity[1]
+                - deep_space::SIDEREAL_SPEED / 60.0 * position[0],
+            prediction.velocity[2],
+        ];
+        Ok((
+            prediction,
+            Prediction {
+                position: position,
+                velocity: velocity,
+            },
+        ))
+    }
+
+    /// Calculates the SGP4 position and velocity predictions in the perifocal frame
+    ///
+    /// The perifocal (PQW) frame is attached to the osculating orbit at the prediction time:
+    /// W is along the angular momentum h = r × v, P points towards the osculating perigee
+    /// (along the eccentricity vector e = ((v² - μ / r) r - (r · v) v) / μ),
+    /// and Q = W × P completes the right-handed frame. The orbit lies in the XY plane.
+    /// In terms of the osculating elements, r_PQW = R₃(ω) R₁(I) R₃(Ω) r_TEME, hence
+    /// r_PQW = r (cos ν, sin ν, 0) with the true anomaly ν = u - ω, where r and u are the radius
+    /// and argument of latitude calculated by SGP4 before the final rotation to TEME,
+    /// and ω is the osculating argument of perigee.
+    /// If the osculating eccentricity vanishes, P points towards the ascending node
+    /// (towards TEME x if the orbit is also equatorial).
+    ///
+    /// # Arguments
+    /// `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("MOLNIYA 1-36".to_owned()),
+    ///         "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813".as_bytes(),
+    ///         "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let prediction = constants.propagate_perifocal(60.0)?;
+    /// assert!(prediction.position[2].abs() < 1.0e-6);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_perifocal(&self, t: f64) -> Result<Prediction> {
+        let prediction = self.propagate(t)?;
+        let (r, v) = (prediction.position, prediction.velocity);
+        let mu = self.geopotential.mu();
+        let h = model::cross(&r, &v);
+        let w = model::normalize(&h);
+        let r_norm = model::norm(&r);
+        let v_squared = model::dot(&v, &v);
+        let r_dot_v = model::dot(&r, &v);
+        let e = [
+            ((v_squared - mu / r_norm) * r[0] - r_dot_v * v[0]) / mu,
+            ((v_squared - mu / r_norm) * r[1] - r_dot_v * v[1]) / mu,
+            ((v_squared - mu / r_norm) * r[2] - r_dot_v * v[2]) / mu,
+        ];
+        let p = if model::norm(&e) > 1.0e-12 {
+            model::normalize(&e)
+        } else {
+            // the ascending node direction ẑ × W
+            let node = [-w[1], w[0], 0.0];
+            if model::norm(&node) > 1.0e-12 {
+                model::normalize(&node)
+            } else {
+                [1.0, 0.0, 0.0]
+            }
+        };
+        let q = model::cross(&w, &p);
+        Ok(Prediction {
+            position: [model::dot(&p, &r), model::dot(&q, &r), model::dot(&w, &r)],
+            velocity: [model::dot(&p, &v), model::dot(&q, &v), model::dot(&w, &v)],
+        })
+    }
+
+    /// Calculates the position and velocity predictions of an unperturbed Keplerian orbit
+    ///
+    /// The Brouwer elements at epoch are propagated without the J₂, J₃ and J₄ perturbations,
+    /// drag and third-body effects: only the mean anomaly advances, at the rate n₀".
+    /// This is meant for debugging, to isolate the contribution of the perturbations.
+    ///
+    /// # Arguments
+    /// `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let perturbed = constants.propagate(60.0 * 24.0)?;
+    /// let unperturbed = constants.propagate_two_body(60.0 * 24.0)?;
+    /// println!("Δx = {} km", perturbed.position[0] - unperturbed.position[0]);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_two_body(&self, t: f64) -> Result<Prediction> {
+        let orbit = &self.orbit_0;
+
+        // a = a₀"
+        let a = self.initial_semi_major_axis();
+
+        // M = M₀ + n₀" t
+        let mean_anomaly =
+            (orbit.mean_anomaly + orbit.mean_motion * t).rem_euclid(2.0 * std::f64::consts::PI);
+
+        // E - e sin E = M
+        let eccentric_anomaly = model::kepler_solve(mean_anomaly, orbit.eccentricity);
+
+        // β = (1 - e²)¹ᐟ²
+        let b = (1.0 - orbit.eccentricity.powi(2)).sqrt();
+
+        // perifocal position and velocity in earth radii and earth radii.min⁻¹
+        let x = a * (eccentric_anomaly.cos() - orbit.eccentricity);
+        let y = a * b * eccentric_anomaly.sin();
+        let rate = orbit.mean_motion * a / (1.0 - orbit.eccentricity * eccentric_anomaly.cos());
+        let x_dot = -rate * eccentric_anomaly.sin();
+        let y_dot = rate * b * eccentric_anomaly.cos();
+
+        // P = [cos Ω cos ω - sin Ω sin ω cos I, sin Ω cos ω + cos Ω sin ω cos I, sin ω sin I]
+        let p = [
+            orbit.right_ascension.cos() * orbit.argument_of_perigee.cos()
+                - orbit.right_ascension.sin()
+                    * orbit.argument_of_perigee.sin()
+                    * orbit.inclination.cos(),
+            orbit.right_ascension.sin() * orbit.argument_of_perigee.cos()
+                + orbit.right_ascension.cos()
+                    * orbit.argument_of_perigee.sin()
+                    * orbit.inclination.cos(),
+            orbit.argument_of_perigee.sin() * orbit.inclination.sin(),
+        ];
+
+        // Q = [- cos Ω sin ω - sin Ω cos ω cos I, - sin Ω sin ω + cos Ω cos ω cos I, cos ω sin I]
+        let q = [
+            -orbit.right_ascension.cos() * orbit.argument_of_perigee.sin()
+                - orbit.right_ascension.sin()
+                    * orbit.argument_of_perigee.cos()
+                    * orbit.inclination.cos(),
+            -orbit.right_ascension.sin() * orbit.argument_of_perigee.sin()
+                + orbit.right_ascension.cos()
+                    * orbit.argument_of_perigee.cos()
+                    * orbit.inclination.cos(),
+            orbit.argument_of_perigee.cos() * orbit.inclination.sin(),
+        ];
+        Ok(Prediction {
+            position: [
+                (x * p[0] + y * q[0]) * self.geopotential.ae,
+                (x * p[1] + y * q[1]) * self.geopotential.ae,
+                (x * p[2] + y * q[2]) * self.geopotential.ae,
+            ],
+            velocity: [
+                (x_dot * p[0] + y_dot * q[0]) * (self.geopotential.ae / 60.0),
+                (x_dot * p[1] + y_dot * q[1]) * (self.geopotential.ae / 60.0),
+                (x_dot * p[2] + y_dot * q[2]) * (self.geopotential.ae / 60.0),
+            ],
+        })
+    }
+
+    /// Calculates the SGP4 position and velocity predictions and intermediate derived quantities
+    ///
+    /// The semi-latus rectum and the specific angular momentum are byproducts of the propagation,
+    /// useful for energy and momentum diagnostics or for converting to other element sets.
+    ///
+    /// # Arguments
+    /// `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let (prediction, derived) = constants.propagate_with_derived(60.0 * 24.0)?;
+    /// println!("pₗ = {} km", derived.semi_latus_rectum);
+    /// println!("h = {} km².s⁻¹", derived.specific_angular_momentum);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_with_derived(&self, t: f64) -> Result<(Prediction, DerivedQuantities)> {
+        self.propagate_from_state_with_derived(t, self.initial_state().as_mut(), false, None, None)
+    }
+
+    /// Calculates the SGP4 position and velocity predictions and the Kepler solver iterates
+    ///
+    /// SGP4 solves Kepler's equation for E + ω (the eccentric anomaly plus the argument of perigee)
+    /// with at most 10 Newton-Raphson iterations, each step being clamped to [-0.95, 0.95] rad
+    /// (see `Constants::with_kepler_solver` for an alternative).
+    /// The returned vector starts with the initial guess (M + ω with the long-period corrections)
+    /// and contains each successive iterate. It is meant to inspect the solver's convergence,
+    /// for example on highly eccentric orbits.
+    ///
+    /// # Arguments
+    /// `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("MOLNIYA 1-36".to_owned()),
+    ///         "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813".as_bytes(),
+    ///         "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let (prediction, iterates) = constants.propagate_verbose(60.0)?;
+    /// for (index, ew) in iterates.iter().enumerate() {
+    ///     println!("(E + ω){} = {} rad", index, ew);
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_verbose(&self, t: f64) -> Result<(Prediction, Vec<f64>)> {
+        let mut iterates = Vec::new();
+        let (prediction, _) = self.propagate_from_state_with_derived(
+            t,
+            self.initial_state().as_mut(),
+            false,
+            Some(&mut iterates),
+            None,
+        )?;
+        Ok((prediction, iterates))
+    }
+
+    /// Calculates the SGP4 position and velocity predictions and reports non-fatal degradations
+    ///
+    /// The prediction is identical to `Constants::propagate`. The warnings flag predictions that
+    /// succeed in a degraded regime: a mean eccentricity clamped to 10⁻⁶, a near-180° inclination
+    /// for which the long-period terms are singular, a Kepler solver that did not converge,
+    /// or a prediction time outside `Constants::recommended_time_range`.
+    /// An empty vector means that none of these occurred.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let (prediction, warnings) = constants.propagate_checked(60.0)?;
+    /// assert!(warnings.is_empty());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_checked(&self, t: f64) -> Result<(Prediction, Vec<Warning>)> {
+        let mut warnings = Vec::new();
+        let (t_min, t_max) = self.recommended_time_range();
+        if !(t_min..=t_max).contains(&t) {
+            warnings.push(Warning::OutsideRecommendedTimeRange { t: t });
+        }
+        let (prediction, _) = self.propagate_from_state_with_derived(
+            t,
+            self.initial_state().as_mut(),
+            false,
+            None,
+            Some(&mut warnings),
+        )?;
+        Ok((prediction, warnings))
+    }
+
+    /// Returns the solar and lunar long-period periodic effects at the given time
+    ///
+    /// Each array contains the five contributions (δe, δI, δM, p₄, p₅) of a third body,
+    /// which are summed by the deep space propagator before they are applied to the elements.
+    /// They correspond to (ses, sis, sls, sghs, shs) and (sel, sil, sll, sghl, shll) in the reference
+    /// implementation (`dpper`) and can be used to cross-validate a port of the deep space model.
+    /// `None` is returned for near earth orbits.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("MOLNIYA 1-36".to_owned()),
+    ///         "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813".as_bytes(),
+    ///         "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// if let Some((solar, lunar)) = constants.third_body_periodic_effects(720.0) {
+    ///     println!("δe = {} + {}", solar[0], lunar[0]);
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn third_body_periodic_effects(&self, t: f64) -> Option<([f64; 5], [f64; 5])> {
+        match &self.method {
+            propagator::Method::NearEarth { .. } => None,
+            propagator::Method::DeepSpace {
+                solar_perturbations,
+                lunar_perturbations,
+                ..
+            } => Some(deep_space::long_period_periodic_effects(
+                solar_perturbations,
+                lunar_perturbations,
+                t,
+            )),
+        }
+    }
+
+    /// Returns the state transition matrix between epoch and the given time
+    ///
+    /// The matrix Φ(t) approximates the partial derivatives ∂x(t) / ∂x(0) of the predicted state
+    /// x = (x, y, z, ẋ, ẏ, ż) at `t` with respect to the predicted state at epoch,
+    /// with positions in km and velocities in km.s⁻¹.
+    /// It is obtained by central finite differences on the Brouwer elements at epoch (mapped through `Orbit`),
+    /// Φ(t) = (∂x(t) / ∂p) (∂x(0) / ∂p)⁻¹, and can be used to propagate a state covariance
+    /// P(t) = Φ(t) P(0) Φ(t)ᵀ. The drag term is not perturbed.
+    ///
+    /// # Arguments
+    /// `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let state_transition = constants.state_transition(60.0)?;
+    /// println!("∂x(t) / ∂x(0) = {}", state_transition[0][0]);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn state_transition(&self, t: f64) -> Result<[[f64; 6]; 6]> {
+        let jacobian_0 = self.state_jacobian(0.0)?;
+        let jacobian_t = self.state_jacobian(t)?;
+
+        // solves (∂x(0) / ∂p)ᵀ Φᵢ = (∂xᵢ(t) / ∂p) for each row Φᵢ
+        let mut transposed = [[0.0; 6]; 6];
+        for (row, values) in jacobian_0.iter().enumerate() {
+            for (column, value) in values.iter().enumerate() {
+                transposed[column][row] = *value;
+            }
+        }
+        let mut state_transition = [[0.0; 6]; 6];
+        for (row, values) in state_transition.iter_mut().zip(jacobian_t.iter()) {
+            *row = fit::solve(transposed, *values)
+                .ok_or_else(|| Error::new("singular state jacobian".to_owned()))?;
+        }
+        Ok(state_transition)
+    }
+
+    /// Propagates a mean elements covariance to a state covariance at the given time
+    ///
+    /// The covariance P₀ of the Brouwer elements at epoch is mapped to the covariance of the predicted
+    /// state x = (x, y, z, ẋ, ẏ, ż) at `t` with P(t) = J(t) P₀ J(t)ᵀ, where J(t) = ∂x(t) / ∂p
+    /// is calculated with the central finite differences used by `Constants::state_transition`.
+    /// The elements p are ordered as (I, Ω, e cos ω, e sin ω, M + ω, n₀"), with angles in rad
+    /// and the mean motion in rad.min⁻¹. The state covariance is given in km² (positions)
+    /// and km².s⁻² (velocities) in the True Equator, Mean Equinox frame. The drag term is not perturbed.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    /// * `p0` - The covariance of the Brouwer elements at epoch
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let mut p0 = [[0.0; 6]; 6];
+    /// for (index, variance) in [1.0e-10, 1.0e-10, 1.0e-12, 1.0e-12, 1.0e-10, 1.0e-16]
+    ///     .iter()
+    ///     .enumerate()
+    /// {
+    ///     p0[index][index] = *variance;
+    /// }
+    /// let covariance = constants.propagate_covariance(1440.0, p0)?;
+    /// println!("σx = {} km", covariance[0][0].sqrt());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_covariance(&self, t: f64, p0: [[f64; 6]; 6]) -> Result<[[f64; 6]; 6]> {
+        let jacobian = self.state_jacobian(t)?;
+
+        // J P₀
+        let mut product = [[0.0; 6]; 6];
+        for (product_row, jacobian_row) in product.iter_mut().zip(jacobian.iter()) {
+            for (column, value) in product_row.iter_mut().enumerate() {
+                *value = (0..6)
+                    .map(|index| jacobian_row[index] * p0[index][column])
+                    .sum();
+            }
+        }
+
+        // (J P₀) Jᵀ
+        let mut covariance = [[0.0; 6]; 6];
+        for (covariance_row, product_row) in covariance.iter_mut().zip(product.iter()) {
+            for (value, jacobian_row) in covariance_row.iter_mut().zip(jacobian.iter()) {
+                *value = (0..6)
+                    .map(|index| product_row[index] * jacobian_row[index])
+                    .sum();
+            }
+        }
+        Ok(covariance)
+    }
+
+    /// Calculates the SGP4 position and velocity predictions with an explicit time unit
+    ///
+    /// # Arguments
+    ///
+    /// * `time` - The time since epoch, for example `PropagationTime::from_seconds(30.0)` or a `chrono::Duration`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let prediction = constants.propagate_time(chrono::Duration::hours(6))?;
+    /// let prediction = constants.propagate_time(sgp4::PropagationTime::from_days(0.25))?;
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_time(&self, time: impl Into<PropagationTime>) -> Result<Prediction> {
+        self.propagate(time.into().minutes)
+    }
+
+    /// Calculates the SGP4 position and velocity predictions and labels them with their time
+    ///
+    /// The record bundles the propagation time in minutes since epoch, the corresponding
+    /// UTC timestamp and the prediction, so that exported rows cannot be mislabeled.
+    /// The timestamp is calculated from the epoch stored in the propagator (J2000 + epoch + t)
+    /// and rounded to the microsecond. Leap seconds between the epoch and the prediction are ignored,
+    /// as in SGP4.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// for index in 0..10 {
+    ///     let record = constants.propagate_record(index as f64 * 10.0)?;
+    ///     println!(
+    ///         "{},{},{},{},{}",
+    ///         record.datetime, record.t, record.position[0], record.position[1], record.position[2]
+    ///     );
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_record(&self, t: f64) -> Result<Record> {
+        let prediction = self.propagate(t)?;
+
+        // Δ = (365.25 × 24 × 60 y₂₀₀₀ + t) × 60 × 10⁶ µs since J2000
+        let microseconds = ((self.epoch * (365.25 * 24.0 * 60.0) + t) * 60.0e6).round();
+        let datetime = if microseconds.is_finite() && microseconds.abs() < i64::MAX as f64 {
+            chrono::NaiveDate::from_ymd_opt(2000, 1, 1)
+                .and_then(|date| date.and_hms_opt(12, 0, 0))
+                .and_then(|j2000| {
+                    j2000.checked_add_signed(chrono::Duration::microseconds(microseconds as i64))
+                })
+        } else {
+            None
+        };
+        Ok(Record {
+            t: t,
+            datetime: datetime
+                .ok_or_else(|| Error::new("the prediction timestamp is out of range".to_owned()))?,
+            position: prediction.position,
+            velocity: prediction.velocity,
+        })
+    }
+
+    /// Calculates the SGP4 position and velocity predictions at a hifitime epoch
+    ///
+    /// The epoch is converted to UTC and the number of minutes since the elements' epoch
+    /// is calculated from the epoch stored in the propagator. Leap seconds between the two epochs are ignored,
+    /// as in SGP4. This method requires the `hifitime` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `epoch` - The time of the prediction
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("ISS (ZARYA)".to_owned()),
+    ///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    /// )?;
+    /// let constants = sgp4::Constants::from_elements(&elements)?;
+    /// let prediction = constants.propagate_epoch(
+    ///     elements.epoch_hifitime() + hifitime::Duration::from_f64(60.0, hifitime::Unit::Minute),
+    /// )?;
+    /// let expected = constants.propagate(60.0)?;
+    /// for index in 0..3 {
+    ///     assert!((prediction.position[index] - expected.position[index]).abs() < 1.0e-3);
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "hifitime")]
+    pub fn propagate_epoch(&self, epoch: hifitime::Epoch) -> Result<Prediction> {
+        let (year, month, day, hour, minute, second, nanosecond) = epoch.to_gregorian_utc();
+        let datetime = chrono::NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+            .and_then(|date| {
+                date.and_hms_nano_opt(hour as u32, minute as u32, second as u32, nanosecond)
+            })
+            .ok_or_else(|| Error::new("the epoch is out of range".to_owned()))?;
+        self.propagate(self.minutes_since_epoch(&datetime))
+    }
+
+    // returns the number of minutes between the propagator's epoch and a UTC timestamp
+    // leap seconds are ignored, as in SGP4
+    fn minutes_since_epoch(&self, datetime: &chrono::naive::NaiveDateTime) -> f64 {
+        (Epoch::from_datetime(datetime).years_since_j2000() - self.epoch) * (365.25 * 24.0 * 60.0)
+    }
+
+    /// Calculates the SGP4 position and velocity predictions at the current system time
+    ///
+    /// The system clock is read as UTC and converted to minutes since the propagator's epoch.
+    /// Leap seconds between the epoch and the current time are ignored, as in SGP4.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// match constants.propagate_now() {
+    ///     Ok(prediction) => println!("r = {:?} km", prediction.position),
+    ///     Err(error) => println!("the elements are too old: {}", error),
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_now(&self) -> Result<Prediction> {
+        self.propagate(self.minutes_since_epoch(&chrono::Utc::now().naive_utc()))
+    }
+
+    /// Calculates the SGP4 position and velocity predictions at a time given in seconds
+    ///
+    /// # Arguments
+    ///
+    /// * `seconds` - The number of seconds since epoch (can be positive, negative or zero)
+    pub fn propagate_seconds(&self, seconds: f64) -> Result<Prediction> {
+        self.propagate_time(PropagationTime::from_seconds(seconds))
+    }
+
+    /// Calculates the SGP4 position and velocity predictions at a time given in days
+    ///
+    /// # Arguments
+    ///
+    /// * `days` - The number of days since epoch (can be positive, negative or zero)
+    pub fn propagate_days(&self, days: f64) -> Result<Prediction> {
+        self.propagate_time(PropagationTime::from_days(days))
+    }
+
+    /// Calculates the SGP4 position and velocity predictions and writes them to an existing prediction
+    ///
+    /// This method is equivalent to `Constants::propagate` but lets the caller own the output buffer,
+    /// which is convenient in real-time loops. `prediction` is left untouched if the propagation fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    /// * `prediction` - The prediction to overwrite with the position and velocity at `t`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let mut prediction = sgp4::Prediction {
+    ///     position: [0.0; 3],
+    ///     velocity: [0.0; 3],
+    /// };
+    /// for minutes in 0..60 {
+    ///     constants.propagate_into(minutes as f64, &mut prediction)?;
+    ///     println!("r = {:?} km", prediction.position);
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_into(&self, t: f64, prediction: &mut Prediction) -> Result<()> {
+        *prediction = self.propagate(t)?;
+        Ok(())
+    }
+
+    /// Calculates the SGP4 position and velocity predictions at several times and writes them to an existing slice
+    ///
+    /// This is the batch counterpart of `Constants::propagate_into`: the caller owns the output
+    /// and no memory is allocated. Each time is propagated independently,
+    /// so a failure at one time does not prevent the others from being calculated.
+    ///
+    /// # Arguments
+    ///
+    /// * `times` - The numbers of minutes since epoch (can be positive, negative or zero)
+    /// * `out` - The slice to overwrite with the propagation result at each time
+    ///
+    /// # Panics
+    ///
+    /// Panics if `times` and `out` do not have the same length.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let times = [0.0, 30.0, 60.0];
+    /// let empty = || {
+    ///     Ok(sgp4::Prediction {
+    ///         position: [0.0; 3],
+    ///         velocity: [0.0; 3],
+    ///     })
+    /// };
+    /// let mut out = [empty(), empty(), empty()];
+    /// constants.propagate_slice(&times, &mut out);
+    /// for result in &out {
+    ///     match result {
+    ///         Ok(prediction) => println!("r = {:?} km", prediction.position),
+    ///         Err(error) => println!("{}", error),
+    ///     }
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_slice(&self, times: &[f64], out: &mut [Result<Prediction>]) {
+        assert_eq!(
+            times.len(),
+            out.len(),
+            "times and out must have the same length"
+        );
+        for (t, result) in times.iter().zip(out.iter_mut()) {
+            *result = self.propagate(*t);
+        }
+    }
+
+    /// Propagates at regularly spaced times and writes the predictions as CSV
+    ///
+    /// The first row is the header `t,x,y,z,vx,vy,vz`. Each following row contains the number of minutes since epoch,
+    /// the TEME position in km and the TEME velocity in km.s⁻¹. Numbers are written with the shortest representation
+    /// that parses back to the same `f64`. The writer is not buffered by this method, wrap it in a `std::io::BufWriter`
+    /// when writing to a file. Writing stops at the first propagation or I/O error.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination of the CSV data, for example a file or a `Vec<u8>`
+    /// * `start` - The number of minutes since epoch of the first row
+    /// * `step` - The interval between rows in minutes
+    /// * `count` - The number of rows, excluding the header
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let mut csv = Vec::new();
+    /// constants.write_csv(&mut csv, 0.0, 1.0, 60)?;
+    /// let csv = String::from_utf8(csv).unwrap();
+    /// assert_eq!(csv.lines().count(), 61);
+    /// assert!(csv.starts_with("t,x,y,z,vx,vy,vz\n0,"));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn write_csv<W: std::io::Write>(
+        &self,
+        mut writer: W,
+        start: f64,
+        step: f64,
+        count: usize,
+    ) -> Result<()> {
+        writeln!(writer, "t,x,y,z,vx,vy,vz")?;
+        for index in 0..count {
+            let t = start + step * index as f64;
+            let prediction = self.propagate(t)?;
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                t,
+                prediction.position[0],
+                prediction.position[1],
+                prediction.position[2],
+                prediction.velocity[0],
+                prediction.velocity[1],
+                prediction.velocity[2],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Propagates at regularly spaced times and writes the predictions as a CCSDS Orbit Ephemeris Message (OEM)
+    ///
+    /// The message follows the KVN (keyword = value notation) format of CCSDS 502.0-B-2, with a single segment
+    /// centered on the Earth and UTC timestamps. States are written in km and km.s⁻¹, in the TEME frame
+    /// or in the J2000 frame (EME2000) depending on `metadata.frame`.
+    /// The first state is at `start` and the last one is the latest multiple of `step` after `start` that does not exceed `end`.
+    /// Writing stops at the first propagation or I/O error.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The destination of the message, for example a file or a `Vec<u8>`
+    /// * `start` - The number of minutes since epoch of the first state
+    /// * `end` - The number of minutes since epoch after which no state is written, it must not be smaller than `start`
+    /// * `step` - The interval between states in minutes, it must be positive,
+    ///   and the message must contain at most `MAX_SAMPLES` states
+    /// * `metadata` - The creation date, originator, object and frame written in the header and metadata sections
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let mut oem = Vec::new();
+    /// constants.write_oem(
+    ///     &mut oem,
+    ///     0.0,
+    ///     90.0,
+    ///     1.0,
+    ///     &sgp4::OemMetadata {
+    ///         creation_date: chrono::Utc::now().naive_utc(),
+    ///         originator: "EXAMPLE".to_owned(),
+    ///         object_name: "ISS (ZARYA)".to_owned(),
+    ///         object_id: "1998-067A".to_owned(),
+    ///         frame: sgp4::OemFrame::J2000,
+    ///     },
+    /// )?;
+    /// let oem = String::from_utf8(oem).unwrap();
+    /// assert!(oem.contains("REF_FRAME = EME2000"));
+    /// assert!(oem.contains("START_TIME = 2020-07-12T21:16:01.000"));
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn write_oem<W: std::io::Write>(
+        &self,
+        mut writer: W,
+        start: f64,
+        end: f64,
+        step: f64,
+        metadata: &OemMetadata,
+    ) -> Result<()> {
+        if !step.is_finite() || step <= 0.0 {
+            return Err(Error::new("the step must be positive".to_owned()));
+        }
+        if !start.is_finite() || !end.is_finite() || end < start {
+            return Err(Error::new(
+                "the end must not be smaller than the start".to_owned(),
+            ));
+        }
+        let count = ((end - start) / step).floor() + 1.0;
+        if count > MAX_SAMPLES as f64 {
+            return Err(Error::new(format!(
+                "the time window requires more than {} samples",
+                MAX_SAMPLES
+            )));
+        }
+        let count = count as usize;
+
+        // UTC 1 January 2000 12h00 (J2000)
+        let j2000 = chrono::NaiveDate::from_ymd_opt(2000, 1, 1)
+            .and_then(|date| date.and_hms_opt(12, 0, 0))
+            .ok_or_else(|| Error::new("invalid J2000 date".to_owned()))?;
+        let timestamp = |t: f64| {
+            (j2000
+                + chrono::Duration::nanoseconds(
+                    ((self.epoch * 365.25 * 24.0 * 60.0 + t) * 60.0e9).round() as i64,
+                ))
+            .format("%Y-%m-%dT%H:%M:%S%.6f")
+        };
+        writeln!(writer, "CCSDS_OEM_VERS = 2.0")?;
+        writeln!(
+            writer,
+            "CREATION_DATE = {}",
+            metadata.creation_date.format("%Y-%m-%dT%H:%M:%S")
+        )?;
+        writeln!(writer, "ORIGINATOR = {}", metadata.originator)?;
+        writeln!(writer)?;
+        writeln!(writer, "META_START")?;
+        writeln!(writer, "OBJECT_NAME = {}", metadata.object_name)?;
+        writeln!(writer, "OBJECT_ID = {}", metadata.object_id)?;
+        writeln!(writer, "CENTER_NAME = EARTH")?;
+        writeln!(
+            writer,
+            "REF_FRAME = {}",
+            match metadata.frame {
+                OemFrame::Teme => "TEME",
+                OemFrame::J2000 => "EME2000",
+            }
+        )?;
+        writeln!(writer, "TIME_SYSTEM = UTC")?;
+        writeln!(writer, "START_TIME = {}", timestamp(start))?;
+        writeln!(
+            writer,
+            "STOP_TIME = {}",
+            timestamp(start + step * (count - 1) as f64)
+        )?;
+        writeln!(writer, "META_STOP")?;
+        writeln!(writer)?;
+        for index in 0..count {
+            let t = start + step * index as f64;
+            let prediction = match metadata.frame {
+                OemFrame::Teme => self.propagate(t)?,
+                OemFrame::J2000 => self
+                    .propagate(t)?
+                    .to_j2000(2451545.0 + self.epoch * 365.25 + t / (24.0 * 60.0)),
+            };
+            writeln!(
+                writer,
+                "{} {:.6} {:.6} {:.6} {:.9} {:.9} {:.9}",
+                timestamp(t),
+                prediction.position[0],
+                prediction.position[1],
+                prediction.position[2],
+                prediction.velocity[0],
+                prediction.velocity[1],
+                prediction.velocity[2],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Calculates the SGP4 position and velocity predictions
+    ///
+    /// This method should be used if compatibility with the AFSPC implementation is needed.
+    /// Its behavior is different from `Constants::propagate`
+    /// only if the satellite is on a Lyddane deep space orbit
+    /// (period greater than 225 min and inclination smaller than 0.2 rad).
+    ///
+    /// # Arguments
+    /// `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements_afspc_compatibility_mode(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let prediction = constants.propagate_afspc_compatibility_mode(60.0 * 24.0);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn propagate_afspc_compatibility_mode(&self, t: f64) -> Result<Prediction> {
+        self.propagate_from_state(t, self.initial_state().as_mut(), true)
+    }
+
+    /// Returns the times at which the sub-satellite point crosses the day/night terminator
+    ///
+    /// The sub-satellite point is in daylight when the satellite position and the Sun direction
+    /// (see `epoch_to_sun_direction`) form an acute angle. The crossings are bracketed
+    /// by sampling the orbit every minute and refined by bisection to 10⁻⁶ min.
+    /// Crossings separated by less than a minute (grazing geometries) may be missed.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The number of minutes since epoch at which the search starts
+    /// * `duration` - The length of the search window in minutes, finite and at most `MAX_SAMPLES` minutes
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// for t in constants.terminator_crossings(0.0, 60.0 * 24.0)? {
+    ///     println!("terminator crossing at t = {} min", t);
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn terminator_crossings(&self, start: f64, duration: f64) -> Result<Vec<f64>> {
+        if duration.is_nan() || duration < 0.0 {
+            return Err(Error::new("the duration must be positive".to_owned()));
+        }
+        let illumination = |t: f64| -> Result<f64> {
+            let position = self.propagate(t)?.position;
+            let sun = epoch_to_sun_direction(self.epoch + t / (365.25 * 24.0 * 60.0));
+            Ok(model::dot(&position, &sun))
+        };
+        let mut crossings = Vec::new();
+        let steps = propagator::step_count(duration, 1.0)?;
+        let mut t0 = start;
+        let mut f0 = illumination(t0)?;
+        for step in 1..=steps {
+            let t1 = (start + step as f64).min(start + duration);
+            let f1 = illumination(t1)?;
+            if (f0 < 0.0) != (f1 < 0.0) {
+                let (mut low, mut high, mut f_low) = (t0, t1, f0);
+                while high - low > 1.0e-6 {
+                    let middle = 0.5 * (low + high);
+                    let f_middle = illumination(middle)?;
+                    if (f_low < 0.0) == (f_middle < 0.0) {
+                        low = middle;
+                        f_low = f_middle;
+                    } else {
+                        high = middle;
+                    }
+                }
+                crossings.push(0.5 * (low + high));
+            }
+            t0 = t1;
+            f0 = f1;
+        }
+        Ok(crossings)
+    }
+
+    /// Calculates the azimuth, elevation and range of the satellite seen from a ground observer
+    ///
+    /// The satellite position is rotated to the Earth-fixed frame with `Constants::propagate_frames`
+    /// and projected on the observer's local east, north and up axes.
+    /// The angular rates are calculated from the Earth-fixed velocity, for antenna tracking.
+    /// Polar motion and atmospheric refraction are neglected.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The observer's position, either `Geodetic` or `EcefObserver`
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let observer = sgp4::Geodetic {
+    ///     latitude: 48.8566 * (std::f64::consts::PI / 180.0),
+    ///     longitude: 2.3522 * (std::f64::consts::PI / 180.0),
+    ///     altitude: 0.035,
+    /// };
+    /// let look_angles = constants.look_angles(observer, 60.0)?;
+    /// println!("elevation = {} rad", look_angles.elevation);
+    /// let look_angles = constants.look_angles(sgp4::EcefObserver([4200.9, 172.6, 4780.1]), 60.0)?;
+    /// println!("elevation = {} rad", look_angles.elevation);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn look_angles(&self, observer: impl Into<EcefObserver>, t: f64) -> Result<LookAngles> {
+        let observer = observer.into();
+        let (latitude, longitude, _) =
+            model::ecef_to_geodetic(&observer.0, model::WGS84.ae, model::WGS84_FLATTENING);
+        self.look_angles_from_ecef(&observer.0, latitude, longitude, t)
+    }
 
-            // k₀ = - ⁷/₂ p₂ p₁₁ p₁ C₁
-            let k0 = 3.5 * p2 * (-p11 * p1) * c1;
+    // calculates the look angles of an observer given by its Earth-fixed position (km)
+    // and its geodetic latitude and longitude (rad), which define the local east, north and up axes
+    fn look_angles_from_ecef(
+        &self,
+        observer_position: &[f64; 3],
+        latitude: f64,
+        longitude: f64,
+        t: f64,
+    ) -> Result<LookAngles> {
+        let (_, prediction) = self.propagate_frames(t)?;
+        let relative_position = [
+            prediction.position[0] - observer_position[0],
+            prediction.position[1] - observer_position[1],
+            prediction.position[2] - observer_position[2],
+        ];
+        let (sin_latitude, cos_latitude) = latitude.sin_cos();
+        let (sin_longitude, cos_longitude) = longitude.sin_cos();
+        let east = -sin_longitude * relative_position[0] + cos_longitude * relative_position[1];
+        let north = -sin_latitude * cos_longitude * relative_position[0]
+            - sin_latitude * sin_longitude * relative_position[1]
+            + cos_latitude * relative_position[2];
+        let up = cos_latitude * cos_longitude * relative_position[0]
+            + cos_latitude * sin_longitude * relative_position[1]
+            + sin_latitude * relative_position[2];
+        let range = model::norm(&[east, north, up]);
 
-            // k₁ = ³/₂ C₁
-            let k1 = 1.5 * c1;
+        // the observer is fixed in the Earth-fixed frame,
+        // hence the relative velocity is the satellite's Earth-fixed velocity
+        let east_rate =
+            -sin_longitude * prediction.velocity[0] + cos_longitude * prediction.velocity[1];
+        let north_rate = -sin_latitude * cos_longitude * prediction.velocity[0]
+            - sin_latitude * sin_longitude * prediction.velocity[1]
+            + cos_latitude * prediction.velocity[2];
+        let up_rate = cos_latitude * cos_longitude * prediction.velocity[0]
+            + cos_latitude * sin_longitude * prediction.velocity[1]
+            + sin_latitude * prediction.velocity[2];
 
-            if orbit_0.mean_motion > 2.0 * std::f64::consts::PI / 225.0 {
-                Ok(near_earth::constants(
-                    geopotential,
-                    drag_term,
-                    orbit_0,
-                    p1,
-                    a0,
-                    s,
-                    xi,
-                    eta,
-                    c1,
-                    c4,
-                    k0,
-                    k1,
-                    k6,
-                    k14,
-                    p2,
-                    p3,
-                    p7,
-                    p9,
-                    p14,
-                    p15,
-                ))
+        // ρₕ = (e² + n²)¹ᐟ², bounded below by 1 mm to avoid the zenith singularity
+        let horizontal_range = (east.powi(2) + north.powi(2)).sqrt().max(1.0e-6);
+
+        // ρ̇ = (e ė + n ṅ + u u̇) / ρ
+        let range_rate = (east * east_rate + north * north_rate + up * up_rate) / range;
+        Ok(LookAngles {
+            azimuth: east.atan2(north).rem_euclid(2.0 * std::f64::consts::PI),
+            elevation: (up / range).asin(),
+            range: range,
+            // Ȧ = (n ė - e ṅ) / ρₕ²
+            azimuth_rate: (north * east_rate - east * north_rate) / horizontal_range.powi(2),
+            // ε̇ = (u̇ - u ρ̇ / ρ) / ρₕ
+            elevation_rate: (up_rate - up * range_rate / range) / horizontal_range,
+        })
+    }
+
+    /// Returns the time windows during which the satellite is above an elevation mask
+    ///
+    /// Each window is a (rise, set) pair of times in minutes since epoch.
+    /// See `Constants::passes` for the search method and for the culmination of each window.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The observer's position, either `Geodetic` or `EcefObserver`
+    /// * `start` - The number of minutes since epoch at which the search starts
+    /// * `end` - The number of minutes since epoch at which the search ends,
+    ///   the search interval must be finite and at most `MAX_SAMPLES` minutes
+    /// * `min_elevation` - The elevation mask in rad
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let observer = sgp4::Geodetic {
+    ///     latitude: 48.8566 * (std::f64::consts::PI / 180.0),
+    ///     longitude: 2.3522 * (std::f64::consts::PI / 180.0),
+    ///     altitude: 0.035,
+    /// };
+    /// for (rise, set) in constants.visibility_windows(
+    ///     observer,
+    ///     0.0,
+    ///     60.0 * 24.0,
+    ///     10.0 * (std::f64::consts::PI / 180.0),
+    /// )? {
+    ///     println!("visible from t = {} min to t = {} min", rise, set);
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn visibility_windows(
+        &self,
+        observer: impl Into<EcefObserver>,
+        start: f64,
+        end: f64,
+        min_elevation: f64,
+    ) -> Result<Vec<(f64, f64)>> {
+        Ok(self
+            .passes(observer, start, end, min_elevation)?
+            .iter()
+            .map(|pass| (pass.rise, pass.set))
+            .collect())
+    }
+
+    /// Returns the passes of the satellite above an elevation mask, with their culmination
+    ///
+    /// The elevation is sampled every minute and each mask crossing is refined by bisection to 10⁻⁶ min.
+    /// The highest sample of each pass is tracked during the scan and refined with a golden-section search.
+    /// Passes that are open at `start` or `end` are clipped to the search interval.
+    /// Passes shorter than a minute (grazing geometries) may be missed.
+    ///
+    /// # Arguments
+    ///
+    /// * `observer` - The observer's position, either `Geodetic` or `EcefObserver`
+    /// * `start` - The number of minutes since epoch at which the search starts
+    /// * `end` - The number of minutes since epoch at which the search ends,
+    ///   the search interval must be finite and at most `MAX_SAMPLES` minutes
+    /// * `min_elevation` - The elevation mask in rad
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let observer = sgp4::Geodetic {
+    ///     latitude: 48.8566 * (std::f64::consts::PI / 180.0),
+    ///     longitude: 2.3522 * (std::f64::consts::PI / 180.0),
+    ///     altitude: 0.035,
+    /// };
+    /// let mut passes = constants.passes(
+    ///     observer,
+    ///     0.0,
+    ///     60.0 * 24.0,
+    ///     10.0 * (std::f64::consts::PI / 180.0),
+    /// )?;
+    /// passes.sort_by(|a, b| b.max_elevation.partial_cmp(&a.max_elevation).unwrap());
+    /// for pass in passes {
+    ///     println!(
+    ///         "peak elevation {} rad at t = {} min",
+    ///         pass.max_elevation, pass.culmination
+    ///     );
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn passes(
+        &self,
+        observer: impl Into<EcefObserver>,
+        start: f64,
+        end: f64,
+        min_elevation: f64,
+    ) -> Result<Vec<Pass>> {
+        if start.is_nan() || end.is_nan() || end < start {
+            return Err(Error::new(
+                "the search end must be later than the search start".to_owned(),
+            ));
+        }
+        let observer = observer.into();
+        let (latitude, longitude, _) =
+            model::ecef_to_geodetic(&observer.0, model::WGS84.ae, model::WGS84_FLATTENING);
+        let margin = |t: f64| -> Result<f64> {
+            Ok(self
+                .look_angles_from_ecef(&observer.0, latitude, longitude, t)?
+                .elevation
+                - min_elevation)
+        };
+        let bisect = |mut low: f64, mut high: f64, mut f_low: f64| -> Result<f64> {
+            while high - low > 1.0e-6 {
+                let middle = 0.5 * (low + high);
+                let f_middle = margin(middle)?;
+                if (f_low < 0.0) == (f_middle < 0.0) {
+                    low = middle;
+                    f_low = f_middle;
+                } else {
+                    high = middle;
+                }
+            }
+            Ok(0.5 * (low + high))
+        };
+
+        // returns the pass from its rise and set times and its highest sample
+        let pass = |rise: f64, set: f64, highest: (f64, f64)| -> Result<Pass> {
+            let culmination = fit::minimize(
+                |t| margin(t).map_or(f64::INFINITY, |value| -value),
+                (highest.0 - 1.0).max(rise),
+                (highest.0 + 1.0).min(set),
+                1.0e-6,
+            );
+            let refined = margin(culmination)?;
+            let (culmination, max_margin) = if refined >= highest.1 {
+                (culmination, refined)
             } else {
-                Ok(deep_space::constants(
-                    geopotential,
-                    epoch_to_sidereal_time,
-                    epoch,
-                    orbit_0,
-                    p1,
-                    a0,
-                    c1,
-                    b0,
-                    c4,
-                    k0,
-                    k1,
-                    k14,
-                    p2,
-                    p14,
-                    p15,
-                ))
+                highest
+            };
+            Ok(Pass {
+                rise: rise,
+                set: set,
+                culmination: culmination,
+                max_elevation: max_margin + min_elevation,
+            })
+        };
+        let mut passes = Vec::new();
+        let steps = propagator::step_count(end - start, 1.0)?;
+        let mut t0 = start;
+        let mut f0 = margin(t0)?;
+
+        // rise time and highest sample (time, margin) of the current pass
+        let mut current = if f0 >= 0.0 {
+            Some((start, (start, f0)))
+        } else {
+            None
+        };
+        for step in 1..=steps {
+            let t1 = (start + step as f64).min(end);
+            let f1 = margin(t1)?;
+            if (f0 < 0.0) != (f1 < 0.0) {
+                let crossing = bisect(t0, t1, f0)?;
+                match current.take() {
+                    Some((rise, highest)) => passes.push(pass(rise, crossing, highest)?),
+                    None => current = Some((crossing, (t1, f1))),
+                }
+            } else if let Some((_, highest)) = current.as_mut() {
+                if f1 > highest.1 {
+                    *highest = (t1, f1);
+                }
             }
+            t0 = t1;
+            f0 = f1;
         }
+        if let Some((rise, highest)) = current {
+            passes.push(pass(rise, end, highest)?);
+        }
+        Ok(passes)
     }
 
-    /// Initializes a new propagator from an `Elements` object
+    /// Returns the rate of change of the distance between the satellite and the Sun in km.s⁻¹
     ///
-    /// This is the recommended method to initialize a propagator from a TLE or OMM.
-    /// The WGS84 model, the IAU sidereal time expression and the accurate UTC to J2000 expression are used.
+    /// The range rate is the projection of the relative velocity on the satellite-Sun line of sight.
+    /// A positive value means that the satellite and the Sun move apart (receding).
+    /// The Sun position is calculated with the low-precision solar ephemeris of `epoch_to_sun_direction`
+    /// and the Sun velocity relative to the Earth with a central difference over 2 min.
     ///
     /// # Arguments
     ///
-    /// * `elements` - Orbital elements and drag term parsed from a TLE or OMM
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
     ///
     /// # Example
     ///
@@ -400,364 +4389,383 @@ impl<'a> Constants<'a> {
     ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
     ///     )?,
     /// )?;
+    /// let range_rate = constants.sun_range_rate(60.0)?;
+    /// assert!(range_rate.abs() < 8.5);
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn from_elements(elements: &Elements) -> Result<Self> {
-        Constants::new(
-            &WGS84,
-            iau_epoch_to_sidereal_time,
-            elements.epoch(),
-            elements.drag_term,
-            Orbit::from_kozai_elements(
-                &WGS84,
-                elements.inclination * (std::f64::consts::PI / 180.0),
-                elements.right_ascension * (std::f64::consts::PI / 180.0),
-                elements.eccentricity,
-                elements.argument_of_perigee * (std::f64::consts::PI / 180.0),
-                elements.mean_anomaly * (std::f64::consts::PI / 180.0),
-                elements.mean_motion * (std::f64::consts::PI / 720.0),
-            )?,
-        )
+    pub fn sun_range_rate(&self, t: f64) -> Result<f64> {
+        let prediction = self.propagate(t)?;
+        let sun_position =
+            |t: f64| model::epoch_to_sun_position(self.epoch + t / (365.25 * 24.0 * 60.0));
+        let sun = sun_position(t);
+        let (before, after) = (sun_position(t - 1.0), sun_position(t + 1.0));
+        let mut line_of_sight = [0.0; 3];
+        let mut relative_velocity = [0.0; 3];
+        for index in 0..3 {
+            line_of_sight[index] = sun[index] - prediction.position[index];
+
+            // the Sun velocity is converted from km.min⁻¹ to km.s⁻¹
+            relative_velocity[index] =
+                (after[index] - before[index]) / 120.0 - prediction.velocity[index];
+        }
+        let distance = model::norm(&line_of_sight);
+
+        // ρ̇ = (rₛ - r) · (vₛ - v) / |rₛ - r|
+        Ok(model::dot(&line_of_sight, &relative_velocity) / distance)
     }
 
-    /// Initializes a new propagator from an `Elements` object
+    /// Returns the boundary of the ground area seen within an off-nadir half-angle
     ///
-    /// This method should be used if compatibility with the AFSPC implementation is needed.
-    /// The WGS72 model, the AFSPC sidereal time expression and the AFSPC UTC to J2000 expression are used.
+    /// The sub-satellite point is the geodetic projection of the Earth-fixed position
+    /// (see `Constants::propagate_frames`). The footprint is approximated by a spherical cap
+    /// of radius aₑ centered on the sub-satellite point, with the Earth central angle
+    /// λ = asin(r sin η / aₑ) - η, where r is the geocentric distance and η the half-angle.
+    /// If the cone extends beyond the horizon, the cap is limited to the horizon, λ = acos(aₑ / r).
+    /// The boundary is returned as 72 (latitude, longitude) points in rad, every 5° of azimuth
+    /// clockwise from north, with longitudes in the range ]-π, π].
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    /// * `half_angle` - The off-nadir half-angle of the sensor in rad, in the range [0, π/2[
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// let footprint = constants.swath_footprint(60.0, 30.0 * (std::f64::consts::PI / 180.0))?;
+    /// assert_eq!(footprint.len(), 72);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn swath_footprint(&self, t: f64, half_angle: f64) -> Result<Vec<(f64, f64)>> {
+        if !(0.0..std::f64::consts::FRAC_PI_2).contains(&half_angle) {
+            return Err(Error::new(
+                "the half-angle must be in the range [0, π/2[".to_owned(),
+            ));
+        }
+        let (_, prediction) = self.propagate_frames(t)?;
+        let (latitude, longitude, _) = model::ecef_to_geodetic(
+            &prediction.position,
+            model::WGS84.ae,
+            model::WGS84_FLATTENING,
+        );
+        let ratio = prediction.radius() / self.geopotential.ae;
+
+        // λ = │ asin(r sin η / aₑ) - η if r sin η < aₑ
+        //     │ acos(aₑ / r)           otherwise
+        let central_angle = if ratio * half_angle.sin() < 1.0 {
+            (ratio * half_angle.sin()).asin() - half_angle
+        } else {
+            (1.0 / ratio).acos()
+        };
+        let (sin_latitude, cos_latitude) = latitude.sin_cos();
+        let (sin_central_angle, cos_central_angle) = central_angle.sin_cos();
+        Ok((0..72)
+            .map(|index| {
+                let (sin_azimuth, cos_azimuth) =
+                    (index as f64 * 5.0 * (std::f64::consts::PI / 180.0)).sin_cos();
+
+                // φ = asin(sin φ₀ cos λ + cos φ₀ sin λ cos A)
+                let point_latitude = (sin_latitude * cos_central_angle
+                    + cos_latitude * sin_central_angle * cos_azimuth)
+                    .clamp(-1.0, 1.0)
+                    .asin();
+
+                // L = L₀ + atan2(sin A sin λ cos φ₀, cos λ - sin φ₀ sin φ)
+                let point_longitude = longitude
+                    + (sin_azimuth * sin_central_angle * cos_latitude)
+                        .atan2(cos_central_angle - sin_latitude * point_latitude.sin());
+                (
+                    point_latitude,
+                    point_longitude.sin().atan2(point_longitude.cos()),
+                )
+            })
+            .collect())
+    }
+
+    /// Returns the illumination of the satellite by the Sun at the given time
+    ///
+    /// The Earth is modelled as a sphere of radius aₑ and the Sun as a sphere of radius 695700 km,
+    /// at the position given by the low-precision solar ephemeris of `epoch_to_sun_direction`.
+    /// The state is derived from the apparent angular radii of the two disks seen from the satellite
+    /// (θₑ and θₛ) and their angular separation θ: the satellite is sunlit if θ ≥ θₑ + θₛ,
+    /// in the umbra if θ ≤ θₑ - θₛ, and in the penumbra otherwise. Atmospheric refraction is neglected.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # fn main() -> sgp4::Result<()> {
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
+    /// )?;
+    /// if constants.eclipse_state(60.0)? == sgp4::EclipseState::Umbra {
+    ///     println!("the ISS is in the Earth's shadow");
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn eclipse_state(&self, t: f64) -> Result<EclipseState> {
+        let position = self.propagate(t)?.position;
+        let sun = model::epoch_to_sun_position(self.epoch + t / (365.25 * 24.0 * 60.0));
+        let to_sun = [
+            sun[0] - position[0],
+            sun[1] - position[1],
+            sun[2] - position[2],
+        ];
+        let sun_distance = model::norm(&to_sun);
+        let earth_distance = model::norm(&position);
+
+        // θₛ = asin(Rₛ / |rₛ - r|), θₑ = asin(aₑ / |r|)
+        let sun_radius = (model::SUN_RADIUS / sun_distance).asin();
+        let earth_radius = (self.geopotential.ae / earth_distance).min(1.0).asin();
+
+        // θ = acos(-r · (rₛ - r) / (|r| |rₛ - r|))
+        let separation = (-model::dot(&position, &to_sun) / (earth_distance * sun_distance))
+            .clamp(-1.0, 1.0)
+            .acos();
+        Ok(if separation >= earth_radius + sun_radius {
+            EclipseState::Sunlit
+        } else if separation <= earth_radius - sun_radius {
+            EclipseState::Umbra
+        } else {
+            EclipseState::Penumbra
+        })
+    }
+
+    /// Returns the fraction of a time window spent in the Earth's shadow
+    ///
+    /// The window is divided in steps of at most 6 s and `Constants::eclipse_state` is evaluated
+    /// at the middle of each step. The fraction is in the range [0, 1].
     ///
     /// # Arguments
     ///
-    /// * `elements` - Orbital elements and drag term parsed from a TLE or OMM
+    /// * `start` - The number of minutes since epoch at which the window starts
+    /// * `duration` - The length of the window in minutes, for example the orbital period,
+    ///   finite and at most `MAX_SAMPLES` steps of 6 s
+    /// * `include_penumbra` - Whether the penumbra counts as eclipse (otherwise only the umbra does)
     ///
     /// # Example
     ///
     /// ```
     /// # fn main() -> sgp4::Result<()> {
-    /// let constants = sgp4::Constants::from_elements_afspc_compatibility_mode(
+    /// let constants = sgp4::Constants::from_elements(
     ///     &sgp4::Elements::from_tle(
     ///         Some("ISS (ZARYA)".to_owned()),
     ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
     ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
     ///     )?,
     /// )?;
+    /// let period = constants.osculating_period(0.0)?;
+    /// let umbra = constants.eclipse_fraction(0.0, period, false)?;
+    /// let shadow = constants.eclipse_fraction(0.0, period, true)?;
+    /// assert!(umbra <= shadow && shadow < 0.5);
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn from_elements_afspc_compatibility_mode(elements: &Elements) -> Result<Self> {
-        Constants::new(
-            &WGS72,
-            afspc_epoch_to_sidereal_time,
-            elements.epoch_afspc_compatibility_mode(),
-            elements.drag_term,
-            Orbit::from_kozai_elements(
-                &WGS72,
-                elements.inclination * (std::f64::consts::PI / 180.0),
-                elements.right_ascension * (std::f64::consts::PI / 180.0),
-                elements.eccentricity,
-                elements.argument_of_perigee * (std::f64::consts::PI / 180.0),
-                elements.mean_anomaly * (std::f64::consts::PI / 180.0),
-                elements.mean_motion * (std::f64::consts::PI / 720.0),
-            )?,
-        )
-    }
-
-    /// Returns the initial deep space resonance integrator state
-    ///
-    /// For most orbits, SGP4 propagation is stateless.
-    /// That is, predictions at different times are always calculated from the epoch quantities.
-    /// No calculations are saved by propagating to successive times sequentially.
-    ///
-    /// However, resonant deep space orbits (geosynchronous or Molniya) use an integrator
-    /// to estimate the resonance effects of Earth gravity, with a 720 min time step. If the propagation
-    /// times are monotonic, a few operations per prediction can be saved by re-using the integrator state.
-    ///
-    /// The high-level API `Constants::propagate` re-initializes the state with each propagation for simplicity.
-    /// `Constants::initial_state` and `Constants::propagate_from_state` can be used together
-    /// to speed up resonant deep space satellite propagation.
-    /// For non-deep space or non-resonant orbits, their behavior is identical to `Constants::propagate`.
-    ///
-    /// See `Constants::propagate_from_state` for an example.
-    pub fn initial_state(&self) -> Option<ResonanceState> {
-        match &self.method {
-            propagator::Method::NearEarth { .. } => None,
-            propagator::Method::DeepSpace { resonant, .. } => match resonant {
-                propagator::Resonant::No { .. } => None,
-                propagator::Resonant::Yes { lambda_0, .. } => {
-                    Some(ResonanceState::new(self.orbit_0.mean_motion, *lambda_0))
+    pub fn eclipse_fraction(
+        &self,
+        start: f64,
+        duration: f64,
+        include_penumbra: bool,
+    ) -> Result<f64> {
+        if duration.is_nan() || duration <= 0.0 {
+            return Err(Error::new("the duration must be positive".to_owned()));
+        }
+        let steps = propagator::step_count(duration, 0.1)?;
+        let step = duration / steps as f64;
+        let mut eclipsed = 0;
+        for index in 0..steps {
+            match self.eclipse_state(start + (index as f64 + 0.5) * step)? {
+                EclipseState::Sunlit => (),
+                EclipseState::Penumbra => {
+                    if include_penumbra {
+                        eclipsed += 1;
+                    }
                 }
-            },
+                EclipseState::Umbra => eclipsed += 1,
+            }
         }
+        Ok(eclipsed as f64 / steps as f64)
     }
 
-    /// Calculates the SGP4 position and velocity predictions
-    ///
-    /// This is an advanced API which results in marginally faster propagation than `Constants::propagate` in some cases
-    /// (see `Constants::initial_state` for details), at the cost of added complexity for the user.
+    /// Returns the angle in rad between a solar array normal and the direction of the Sun
     ///
-    /// The propagation times must be monotonic if the same resonance state is used repeatedly.
-    /// The `afspc_compatibility_mode` makes a difference only if the satellite is on a Lyddane deep space orbit
-    /// (period greater than 225 min and inclination smaller than 0.2 rad).
+    /// The spacecraft is assumed to be nadir-pointing, with body axes aligned with the local orbital frame:
+    /// z towards the Earth's center (-r̂), y opposite to the orbit normal (-ĥ), and x = y × z
+    /// (along the velocity for a circular orbit). An angle of 0 means that the array faces the Sun,
+    /// an angle greater than π/2 means that the Sun illuminates its back.
+    /// The Earth's shadow is not taken into account, see `Constants::eclipse_state`.
     ///
     /// # Arguments
     ///
     /// * `t` - The number of minutes since epoch (can be positive, negative or zero)
-    /// * `state` - The deep space propagator state returned by `Constants::initial_state`
-    /// * `afspc_compatibility_mode` - Set to true if compatibility with the AFSPC implementation is needed
+    /// * `array_normal_body` - The array normal in body axes (it does not need to be normalized,
+    ///   but it must be finite and non-zero)
     ///
     /// # Example
     ///
     /// ```
     /// # fn main() -> sgp4::Result<()> {
-    /// let elements = sgp4::Elements::from_tle(
-    ///     Some("MOLNIYA 1-36".to_owned()),
-    ///     "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813".as_bytes(),
-    ///     "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656".as_bytes(),
+    /// let constants = sgp4::Constants::from_elements(
+    ///     &sgp4::Elements::from_tle(
+    ///         Some("ISS (ZARYA)".to_owned()),
+    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    ///     )?,
     /// )?;
-    /// let constants = sgp4::Constants::from_elements(&elements)?;
-    /// let mut state = constants.initial_state();
-    /// for days in 0..7 {
-    ///     println!("t = {} min", days * 60 * 24);
-    ///     let prediction =
-    ///         constants.propagate_from_state((days * 60 * 24) as f64, state.as_mut(), false)?;
-    ///     println!("    r = {:?} km", prediction.position);
-    ///     println!("    ṙ = {:?} km.s⁻¹", prediction.velocity);
+    /// let zenith = constants.solar_incidence_angle(60.0, [0.0, 0.0, -1.0])?;
+    /// let nadir = constants.solar_incidence_angle(60.0, [0.0, 0.0, 1.0])?;
+    /// assert!((zenith + nadir - std::f64::consts::PI).abs() < 1.0e-9);
+    /// assert!(constants.solar_incidence_angle(60.0, [0.0, 0.0, 0.0]).is_err());
+    /// if constants.eclipse_state(60.0)? == sgp4::EclipseState::Sunlit {
+    ///     println!("cos θ = {}", zenith.cos());
     /// }
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn propagate_from_state(
-        &self,
-        t: f64,
-        state: Option<&mut ResonanceState>,
-        afspc_compatibility_mode: bool,
-    ) -> Result<Prediction> {
-        // p₂₂ = Ω₀ + Ω̇ t + k₀ t²
-        let p22 = self.orbit_0.right_ascension + self.right_ascension_dot * t + self.k0 * t.powi(2);
-
-        // p₂₃ = ω₀ + ω̇ t
-        let p23 = self.orbit_0.argument_of_perigee + self.argument_of_perigee_dot * t;
-        let (orbit, a, p32, p33, p34, p35, p36) = match &self.method {
-            propagator::Method::NearEarth {
-                a0,
-                k2,
-                k3,
-                k4,
-                k5,
-                k6,
-                high_altitude,
-            } => {
-                assert!(
-                    state.is_none(),
-                    "state must be None with a near-earth propagator",
-                );
-                self.near_earth_orbital_elements(
-                    *a0,
-                    *k2,
-                    *k3,
-                    *k4,
-                    *k5,
-                    *k6,
-                    high_altitude,
-                    t,
-                    p22,
-                    p23,
-                )
-            }
-            propagator::Method::DeepSpace {
-                eccentricity_dot,
-                inclination_dot,
-                solar_perturbations,
-                lunar_perturbations,
-                resonant,
-            } => self.deep_space_orbital_elements(
-                *eccentricity_dot,
-                *inclination_dot,
-                solar_perturbations,
-                lunar_perturbations,
-                resonant,
-                state,
-                t,
-                p22,
-                p23,
-                afspc_compatibility_mode,
-            ),
-        }?;
-
-        // p₃₇ = 1 / (a (1 - e²))
-        let p37 = 1.0 / (a * (1.0 - orbit.eccentricity.powi(2)));
-
-        // aₓₙ = e cos ω
-        let axn = orbit.eccentricity * orbit.argument_of_perigee.cos();
-
-        // aᵧₙ = e sin ω + p₃₇ p₃₂
-        let ayn = orbit.eccentricity * orbit.argument_of_perigee.sin() + p37 * p32;
-
-        // p₃₈ = M + ω + p₃₇ p₃₅ aₓₙ rem 2π
-        let p38 = (orbit.mean_anomaly + orbit.argument_of_perigee + p37 * p35 * axn)
-            % (2.0 * std::f64::consts::PI);
-
-        // (E + ω)₀ = p₃₈
-        let mut ew = p38;
-        for _ in 0..10 {
-            //             p₃₈ - aᵧₙ cos (E + ω)ᵢ + aₓₙ sin (E + ω)ᵢ - (E + ω)ᵢ
-            // Δ(E + ω)ᵢ = ---------------------------------------------------
-            //                   1 - cos (E + ω)ᵢ aₓₙ - sin (E + ω)ᵢ aᵧₙ
-            let delta = (p38 - ayn * ew.cos() + axn * ew.sin() - ew)
-                / (1.0 - ew.cos() * axn - ew.sin() * ayn);
-
-            if delta.abs() < 1.0e-12 {
-                break;
-            }
-
-            // (E + ω)ᵢ₊₁ = (E + ω)ᵢ + Δ(E + ω)ᵢ|[-0.95, 0.95]
-            ew += if delta < -0.95 {
-                -0.95
-            } else if delta > 0.95 {
-                0.95
-            } else {
-                delta
-            };
+    pub fn solar_incidence_angle(&self, t: f64, array_normal_body: [f64; 3]) -> Result<f64> {
+        let norm = model::norm(&array_normal_body);
+        if !norm.is_finite() || norm == 0.0 {
+            return Err(Error::new(
+                "the array normal must be finite and non-zero".to_owned(),
+            ));
         }
+        let prediction = self.propagate(t)?;
+        let r = prediction.position;
+        let v = prediction.velocity;
 
-        // p₃₉ = aₓₙ² + aᵧₙ²
-        let p39 = axn.powi(2) + ayn.powi(2);
-
-        // pₗ = a (1 - p₃₉)
-        let pl = a * (1.0 - p39);
-        if pl < 0.0 {
-            Err(Error::new("negative semi-latus rectum".to_owned()))
-        } else {
-            // p₄₀ = aₓₙ sin(E + ω) - aᵧₙ cos(E + ω)
-            let p40 = axn * ew.sin() - ayn * ew.cos();
-
-            // r = a (1 - aₓₙ cos(E + ω) + aᵧₙ sin(E + ω))
-            let r = a * (1.0 - (axn * ew.cos() + ayn * ew.sin()));
-
-            // ṙ = a¹ᐟ² p₄₀ / r
-            let r_dot = a.sqrt() * p40 / r;
-
-            // β = (1 - p₃₉)¹ᐟ²
-            let b = (1.0 - p39).sqrt();
-
-            // p₄₁ = p₄₀ / (1 + β)
-            let p41 = p40 / (1.0 + b);
-
-            // p₄₂ = a / r (sin(E + ω) - aᵧₙ - aₓₙ p₄₁)
-            let p42 = a / r * (ew.sin() - ayn - axn * p41);
-
-            // p₄₃ = a / r (cos(E + ω) - aₓₙ + aᵧₙ p₄₁)
-            let p43 = a / r * (ew.cos() - axn + ayn * p41);
-
-            //           p₄₂
-            // u = tan⁻¹ ---
-            //           p₄₃
-            let u = p42.atan2(p43);
-
-            // p₄₄ = 2 p₄₃ p₄₂
-            let p44 = 2.0 * p43 * p42;
-
-            // p₄₅ = 1 - 2 p₄₂²
-            let p45 = 1.0 - 2.0 * p42.powi(2);
-
-            // p₄₆ = (¹/₂ J₂ / pₗ) / pₗ
-            let p46 = 0.5 * self.geopotential.j2 / pl / pl;
-
-            // rₖ = r (1 - ³/₂ p₄₆ β p₃₆) + ¹/₂ (¹/₂ J₂ / pₗ) p₃₃ p₄₅
-            let rk = r * (1.0 - 1.5 * p46 * b * p36)
-                + 0.5 * (0.5 * self.geopotential.j2 / pl) * p33 * p45;
-
-            // uₖ = u - ¹/₄ p₄₆ p₃₄ p₄₄
-            let uk = u - 0.25 * p46 * p34 * p44;
-
-            // Iₖ = I + ³/₂ p₄₆ cos I sin I p₄₅
-            let inclination_k = orbit.inclination
-                + 1.5 * p46 * orbit.inclination.cos() * orbit.inclination.sin() * p45;
+        // z = -r / |r|
+        let z = model::normalize(&[-r[0], -r[1], -r[2]]);
 
-            // Ωₖ = Ω + ³/₂ p₄₆ cos I p₄₄
-            let right_ascension_k =
-                orbit.right_ascension + 1.5 * p46 * orbit.inclination.cos() * p44;
+        // y = -(r × v) / |r × v|
+        let h = model::cross(&r, &v);
+        let y = model::normalize(&[-h[0], -h[1], -h[2]]);
 
-            // ṙₖ = ṙ + n (¹/₂ J₂ / pₗ) p₃₃ / kₑ
-            let rk_dot = r_dot
-                - orbit.mean_motion * (0.5 * self.geopotential.j2 / pl) * p33 * p44
-                    / self.geopotential.ke;
-
-            // rḟₖ = pₗ¹ᐟ² / r + n (¹/₂ J₂ / pₗ) (p₃₃ p₄₅ + ³/₂ p₃₆) / kₑ
-            let rfk_dot = pl.sqrt() / r
-                + orbit.mean_motion * (0.5 * self.geopotential.j2 / pl) * (p33 * p45 + 1.5 * p36)
-                    / self.geopotential.ke;
-
-            // u₀ = - sin Ωₖ cos Iₖ sin uₖ + cos Ωₖ cos uₖ
-            let u0 = -right_ascension_k.sin() * inclination_k.cos() * uk.sin()
-                + right_ascension_k.cos() * uk.cos();
-            // u₁ = cos Ωₖ cos Iₖ sin uₖ + sin Ωₖ cos uₖ
-            let u1 = right_ascension_k.cos() * inclination_k.cos() * uk.sin()
-                + right_ascension_k.sin() * uk.cos();
-            // u₂ = sin Iₖ sin uₖ
-            let u2 = inclination_k.sin() * uk.sin();
-            Ok(Prediction {
-                position: [
-                    // r₀ = rₖ u₀ aₑ
-                    rk * u0 * self.geopotential.ae,
-                    // r₁ = rₖ u₁ aₑ
-                    rk * u1 * self.geopotential.ae,
-                    // r₂ = rₖ u₂ aₑ
-                    rk * u2 * self.geopotential.ae,
-                ],
-                velocity: [
-                    // ṙ₀ = (ṙₖ u₀ + rḟₖ (- sin Ωₖ cos Iₖ cos uₖ - cos Ωₖ sin uₖ)) aₑ kₑ / 60
-                    (rk_dot * u0
-                        + rfk_dot
-                            * (-right_ascension_k.sin() * inclination_k.cos() * uk.cos()
-                                - right_ascension_k.cos() * uk.sin()))
-                        * (self.geopotential.ae * self.geopotential.ke / 60.0),
-                    // ṙ₁ = (ṙₖ u₁ + rḟₖ (cos Ωₖ cos Iₖ cos uₖ - sin Ωₖ sin uₖ)) aₑ kₑ / 60
-                    (rk_dot * u1
-                        + rfk_dot
-                            * (right_ascension_k.cos() * inclination_k.cos() * uk.cos()
-                                - right_ascension_k.sin() * uk.sin()))
-                        * (self.geopotential.ae * self.geopotential.ke / 60.0),
-                    // ṙ₂ = (ṙₖ u₂ + rḟₖ (sin Iₖ cos uₖ)) aₑ kₑ / 60
-                    (rk_dot * u2 + rfk_dot * (inclination_k.sin() * uk.cos()))
-                        * (self.geopotential.ae * self.geopotential.ke / 60.0),
-                ],
-            })
-        }
+        // x = y × z
+        let x = model::cross(&y, &z);
+        let normal = model::normalize(&array_normal_body);
+        let normal = [
+            normal[0] * x[0] + normal[1] * y[0] + normal[2] * z[0],
+            normal[0] * x[1] + normal[1] * y[1] + normal[2] * z[1],
+            normal[0] * x[2] + normal[1] * y[2] + normal[2] * z[2],
+        ];
+        let sun = model::epoch_to_sun_position(self.epoch + t / (365.25 * 24.0 * 60.0));
+        let to_sun = model::normalize(&[sun[0] - r[0], sun[1] - r[1], sun[2] - r[2]]);
+        Ok(model::dot(&normal, &to_sun).clamp(-1.0, 1.0).acos())
     }
 
-    /// Calculates the SGP4 position and velocity predictions
+    /// Returns the minimum and maximum altitudes above the WGS84 ellipsoid reached in a time window
     ///
-    /// This is the recommended method to propagate epoch orbital elements.
+    /// Unlike `Constants::perigee_radius` and `Constants::apogee_radius`, the altitudes include
+    /// the short-period oscillations and the drag decay of the perturbed orbit.
+    /// The altitudes are measured from the WGS84 ellipsoid whatever the geopotential model, as in `Prediction::altitude`.
+    /// The window is sampled every minute and the extrema are refined with a golden-section search.
     ///
     /// # Arguments
-    /// `t` - The number of minutes since epoch (can be positive, negative or zero)
+    ///
+    /// * `start` - The number of minutes since epoch at which the window starts
+    /// * `duration` - The length of the window in minutes, finite and at most `MAX_SAMPLES` minutes
     ///
     /// # Example
     ///
     /// ```
     /// # fn main() -> sgp4::Result<()> {
-    /// let constants = sgp4::Constants::from_elements_afspc_compatibility_mode(
+    /// let constants = sgp4::Constants::from_elements(
     ///     &sgp4::Elements::from_tle(
     ///         Some("ISS (ZARYA)".to_owned()),
     ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
     ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
     ///     )?,
     /// )?;
-    /// let prediction = constants.propagate(60.0 * 24.0);
+    /// let (minimum, maximum) = constants.altitude_extrema(0.0, 93.0)?;
+    /// println!("altitude between {} km and {} km", minimum, maximum);
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn propagate(&self, t: f64) -> Result<Prediction> {
-        self.propagate_from_state(t, self.initial_state().as_mut(), false)
+    pub fn altitude_extrema(&self, start: f64, duration: f64) -> Result<(f64, f64)> {
+        if duration.is_nan() || duration < 0.0 {
+            return Err(Error::new("the duration must be positive".to_owned()));
+        }
+        let altitude = |t: f64| -> Result<f64> { Ok(self.propagate(t)?.altitude()) };
+        let steps = propagator::step_count(duration, 1.0)?;
+        let times = (0..=steps)
+            .map(|step| (start + step as f64).min(start + duration))
+            .collect::<Vec<f64>>();
+        let altitudes = times
+            .iter()
+            .map(|t| altitude(*t))
+            .collect::<Result<Vec<f64>>>()?;
+        let (mut minimum_index, mut maximum_index) = (0, 0);
+        for (index, value) in altitudes.iter().enumerate() {
+            if *value < altitudes[minimum_index] {
+                minimum_index = index;
+            }
+            if *value > altitudes[maximum_index] {
+                maximum_index = index;
+            }
+        }
+        let bracket = |index: usize| {
+            (
+                times[index.saturating_sub(1)],
+                times[(index + 1).min(times.len() - 1)],
+            )
+        };
+        let (low, high) = bracket(minimum_index);
+        let t_minimum = fit::minimize(|t| altitude(t).unwrap_or(f64::INFINITY), low, high, 1.0e-6);
+        let (low, high) = bracket(maximum_index);
+        let t_maximum = fit::minimize(
+            |t| altitude(t).map_or(f64::INFINITY, |value| -value),
+            low,
+            high,
+            1.0e-6,
+        );
+        Ok((
+            altitude(t_minimum)?.min(altitudes[minimum_index]),
+            altitude(t_maximum)?.max(altitudes[maximum_index]),
+        ))
     }
+}
 
-    /// Calculates the SGP4 position and velocity predictions
+impl<'a> StatefulPropagator<'a> {
+    /// Initializes a stateful propagator from epoch quantities
     ///
-    /// This method should be used if compatibility with the AFSPC implementation is needed.
-    /// Its behavior is different from `Constants::propagate`
-    /// only if the satellite is on a Lyddane deep space orbit
-    /// (period greater than 225 min and inclination smaller than 0.2 rad).
+    /// # Arguments
+    ///
+    /// * `constants` - The propagator constants, consumed by the stateful propagator
+    pub fn new(constants: Constants<'a>) -> StatefulPropagator<'a> {
+        StatefulPropagator {
+            state: constants.initial_state(),
+            constants: constants,
+        }
+    }
+
+    /// Returns the wrapped propagator constants
+    pub fn constants(&self) -> &Constants<'a> {
+        &self.constants
+    }
+
+    /// Calculates the SGP4 position and velocity predictions, re-using the resonance integrator state
+    ///
+    /// For resonant deep space orbits, only the time elapsed since the last integrator step is integrated
+    /// when the propagation times move away from epoch. The state is transparently reset when
+    /// they move back towards epoch or cross it. For other orbits, this is equivalent to `Constants::propagate`.
     ///
     /// # Arguments
     /// `t` - The number of minutes since epoch (can be positive, negative or zero)
@@ -766,18 +4774,29 @@ impl<'a> Constants<'a> {
     ///
     /// ```
     /// # fn main() -> sgp4::Result<()> {
-    /// let constants = sgp4::Constants::from_elements_afspc_compatibility_mode(
-    ///     &sgp4::Elements::from_tle(
-    ///         Some("ISS (ZARYA)".to_owned()),
-    ///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
-    ///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
-    ///     )?,
+    /// let elements = sgp4::Elements::from_tle(
+    ///     Some("MOLNIYA 1-36".to_owned()),
+    ///     "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813".as_bytes(),
+    ///     "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656".as_bytes(),
     /// )?;
-    /// let prediction = constants.propagate_afspc_compatibility_mode(60.0 * 24.0);
+    /// let constants = sgp4::Constants::from_elements(&elements)?;
+    /// let mut propagator = sgp4::StatefulPropagator::new(sgp4::Constants::from_elements(&elements)?);
+    /// for t in &[0.0, 720.0, 1440.0, 360.0, -720.0] {
+    ///     let prediction = propagator.step_to(*t)?;
+    ///     assert_eq!(prediction.position, constants.propagate(*t)?.position);
+    /// }
     /// #     Ok(())
     /// # }
     /// ```
-    pub fn propagate_afspc_compatibility_mode(&self, t: f64) -> Result<Prediction> {
-        self.propagate_from_state(t, self.initial_state().as_mut(), true)
+    pub fn step_to(&mut self, t: f64) -> Result<Prediction> {
+        if let Some(state) = &self.state {
+            if (state.t() != 0.0 && state.t().is_sign_positive() != t.is_sign_positive())
+                || t.abs() < state.t().abs()
+            {
+                self.state = self.constants.initial_state();
+            }
+        }
+        self.constants
+            .propagate_from_state(t, self.state.as_mut(), false)
     }
 }
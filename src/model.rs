@@ -1,4 +1,5 @@
 /// Model of the Earth radius and gravitational field
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Geopotential {
     /// Equatorial radius of the earth in km
     // aₑ
@@ -43,6 +44,166 @@ pub const WGS72: Geopotential = Geopotential {
     j4: -0.00000165597,
 };
 
+// serializes a geopotential reference by value
+// deserialized values must match one of the built-in geopotential models
+pub(crate) mod geopotential_reference {
+    use super::{Geopotential, WGS72, WGS84};
+    use serde::{Deserialize, Serialize};
+
+    pub(crate) fn serialize<S>(
+        geopotential: &&Geopotential,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (*geopotential).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<&'static Geopotential, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let geopotential = Geopotential::deserialize(deserializer)?;
+        let matches = |model: &Geopotential| {
+            geopotential.ae == model.ae
+                && geopotential.ke == model.ke
+                && geopotential.j2 == model.j2
+                && geopotential.j3 == model.j3
+                && geopotential.j4 == model.j4
+        };
+        if matches(&WGS84) {
+            Ok(&WGS84)
+        } else if matches(&WGS72) {
+            Ok(&WGS72)
+        } else {
+            Err(serde::de::Error::custom(
+                "only the WGS84 and WGS72 geopotential models can be deserialized",
+            ))
+        }
+    }
+}
+
+// flattening of the WGS84 ellipsoid
+pub(crate) const WGS84_FLATTENING: f64 = 1.0 / 298.257223563;
+
+/// Reference ellipsoid used to calculate geodetic coordinates
+///
+/// The geodetic conversions of the crate use `WGS84_ELLIPSOID` whatever the geopotential model
+/// of the propagator, since the geopotential model only affects the propagation.
+/// A WGS72 ellipsoid (aₑ = 6378.135 km, f = 1 / 298.26) can be passed to `Prediction::to_geodetic_on`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ellipsoid {
+    /// Equatorial radius in km
+    pub equatorial_radius: f64,
+
+    /// Flattening (aₑ - b) / aₑ, where b is the polar radius
+    pub flattening: f64,
+}
+
+/// The WGS84 reference ellipsoid
+pub const WGS84_ELLIPSOID: Ellipsoid = Ellipsoid {
+    equatorial_radius: 6378.137,
+    flattening: WGS84_FLATTENING,
+};
+
+// returns the dot product u · v
+pub(crate) fn dot(u: &[f64; 3], v: &[f64; 3]) -> f64 {
+    u[0] * v[0] + u[1] * v[1] + u[2] * v[2]
+}
+
+// returns the cross product u × v
+pub(crate) fn cross(u: &[f64; 3], v: &[f64; 3]) -> [f64; 3] {
+    [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ]
+}
+
+// returns the Euclidean norm |u|
+pub(crate) fn norm(u: &[f64; 3]) -> f64 {
+    dot(u, u).sqrt()
+}
+
+// returns the unit vector u / |u|
+pub(crate) fn normalize(u: &[f64; 3]) -> [f64; 3] {
+    let norm = norm(u);
+    [u[0] / norm, u[1] / norm, u[2] / norm]
+}
+
+// returns the product of a 3 × 3 matrix and a vector
+pub(crate) fn rotate(matrix: &[[f64; 3]; 3], vector: &[f64; 3]) -> [f64; 3] {
+    [
+        dot(&matrix[0], vector),
+        dot(&matrix[1], vector),
+        dot(&matrix[2], vector),
+    ]
+}
+
+// returns the height of a position (km) above an ellipsoid
+// the height does not depend on the rotation about the polar axis, so TEME positions can be used
+pub(crate) fn ellipsoid_height(
+    position: &[f64; 3],
+    equatorial_radius: f64,
+    flattening: f64,
+) -> f64 {
+    ecef_to_geodetic(position, equatorial_radius, flattening).2
+}
+
+// returns the geodetic latitude (rad), longitude (rad) and height (km) of an Earth-fixed position (km)
+pub(crate) fn ecef_to_geodetic(
+    position: &[f64; 3],
+    equatorial_radius: f64,
+    flattening: f64,
+) -> (f64, f64, f64) {
+    // e² = f (2 - f)
+    let e2 = flattening * (2.0 - flattening);
+
+    // p = (x² + y²)¹ᐟ²
+    let p = (position[0].powi(2) + position[1].powi(2)).sqrt();
+
+    // φ₀ = tan⁻¹(z / (p (1 - e²)))
+    let mut latitude = position[2].atan2(p * (1.0 - e2));
+    let mut height = 0.0;
+    for _ in 0..10 {
+        // N = aₑ / (1 - e² sin²φ)¹ᐟ²
+        let n = equatorial_radius / (1.0 - e2 * latitude.sin().powi(2)).sqrt();
+
+        // h = p cos φ + z sin φ - aₑ² / N
+        height = p * latitude.cos() + position[2] * latitude.sin() - equatorial_radius.powi(2) / n;
+
+        // φ = tan⁻¹(z / (p (1 - e² N / (N + h))))
+        latitude = position[2].atan2(p * (1.0 - e2 * n / (n + height)));
+    }
+
+    // λ = tan⁻¹(y / x)
+    (latitude, position[1].atan2(position[0]), height)
+}
+
+// returns the Earth-fixed position (km) of a point given by its geodetic coordinates (rad, rad, km)
+pub(crate) fn geodetic_to_ecef(
+    latitude: f64,
+    longitude: f64,
+    altitude: f64,
+    equatorial_radius: f64,
+    flattening: f64,
+) -> [f64; 3] {
+    // e² = f (2 - f)
+    let e2 = flattening * (2.0 - flattening);
+
+    // N = aₑ / (1 - e² sin²φ)¹ᐟ²
+    let n = equatorial_radius / (1.0 - e2 * latitude.sin().powi(2)).sqrt();
+    [
+        // x = (N + h) cos φ cos λ
+        (n + altitude) * latitude.cos() * longitude.cos(),
+        // y = (N + h) cos φ sin λ
+        (n + altitude) * latitude.cos() * longitude.sin(),
+        // z = (N (1 - e²) + h) sin φ
+        (n * (1.0 - e2) + altitude) * latitude.sin(),
+    ]
+}
+
 /// Converts an epoch to sidereal time using the IAU expression
 ///
 /// This is the recommended method to calculate the sidereal time.
@@ -65,6 +226,366 @@ pub fn iau_epoch_to_sidereal_time(epoch: f64) -> f64 {
         .rem_euclid(2.0 * std::f64::consts::PI)
 }
 
+/// Converts a UT1 Julian date to Greenwich mean sidereal time using the IAU-82 expression
+///
+/// This is the same polynomial as `iau_epoch_to_sidereal_time`, expressed in Julian centuries
+/// since J2000, so that sidereal time can be calculated from external timestamps without a propagator.
+/// The result is in rad, in the range [0, 2π[.
+///
+/// # Arguments
+///
+/// * `jd` - The Julian date (UT1)
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> sgp4::Result<()> {
+/// let elements = sgp4::Elements::from_tle(
+///     Some("ISS (ZARYA)".to_owned()),
+///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+/// )?;
+/// let gmst = sgp4::gmst_from_julian_date(2451545.0 + elements.epoch() * 365.25);
+/// assert!((gmst - sgp4::iau_epoch_to_sidereal_time(elements.epoch())).abs() < 1.0e-9);
+/// #     Ok(())
+/// # }
+/// ```
+pub fn gmst_from_julian_date(jd: f64) -> f64 {
+    // T = (JD - 2451545) / 36525
+    let t = (jd - 2451545.0) / 36525.0;
+
+    // θ = ¹/₂₄₀ (π / 180) (67310.54841 + (876600 × 3600 + 8640184.812866) T
+    //     + 0.093104 T² - 6.2 × 10⁻⁶ T³) mod 2π
+    ((67310.54841 + (876600.0 * 3600.0 + 8640184.812866) * t + 0.093104 * t.powi(2)
+        - 6.2e-6 * t.powi(3))
+        * (std::f64::consts::PI / 180.0)
+        / 240.0)
+        .rem_euclid(2.0 * std::f64::consts::PI)
+}
+
+// largest terms of the IAU 1980 nutation series
+// multipliers of (l, l', F, D, Ω), Δψ = A + B T and Δε = C + D T in 10⁻⁴ arcsec
+// the omitted terms have amplitudes below 1.5 × 10⁻³ arcsec
+const NUTATION_TERMS: [([f64; 5], f64, f64, f64, f64); 30] = [
+    ([0.0, 0.0, 0.0, 0.0, 1.0], -171996.0, -174.2, 92025.0, 8.9),
+    ([0.0, 0.0, 2.0, -2.0, 2.0], -13187.0, -1.6, 5736.0, -3.1),
+    ([0.0, 0.0, 2.0, 0.0, 2.0], -2274.0, -0.2, 977.0, -0.5),
+    ([0.0, 0.0, 0.0, 0.0, 2.0], 2062.0, 0.2, -895.0, 0.5),
+    ([0.0, 1.0, 0.0, 0.0, 0.0], 1426.0, -3.4, 54.0, -0.1),
+    ([1.0, 0.0, 0.0, 0.0, 0.0], 712.0, 0.1, -7.0, 0.0),
+    ([0.0, 1.0, 2.0, -2.0, 2.0], -517.0, 1.2, 224.0, -0.6),
+    ([0.0, 0.0, 2.0, 0.0, 1.0], -386.0, -0.4, 200.0, 0.0),
+    ([1.0, 0.0, 2.0, 0.0, 2.0], -301.0, 0.0, 129.0, -0.1),
+    ([0.0, -1.0, 2.0, -2.0, 2.0], 217.0, -0.5, -95.0, 0.3),
+    ([1.0, 0.0, 0.0, -2.0, 0.0], -158.0, 0.0, -1.0, 0.0),
+    ([0.0, 0.0, 2.0, -2.0, 1.0], 129.0, 0.1, -70.0, 0.0),
+    ([-1.0, 0.0, 2.0, 0.0, 2.0], 123.0, 0.0, -53.0, 0.0),
+    ([1.0, 0.0, 0.0, 0.0, 1.0], 63.0, 0.1, -33.0, 0.0),
+    ([0.0, 0.0, 0.0, 2.0, 0.0], 63.0, 0.0, -2.0, 0.0),
+    ([-1.0, 0.0, 2.0, 2.0, 2.0], -59.0, 0.0, 26.0, 0.0),
+    ([-1.0, 0.0, 0.0, 0.0, 1.0], -58.0, -0.1, 32.0, 0.0),
+    ([1.0, 0.0, 2.0, 0.0, 1.0], -51.0, 0.0, 27.0, 0.0),
+    ([2.0, 0.0, 0.0, -2.0, 0.0], 48.0, 0.0, 1.0, 0.0),
+    ([-2.0, 0.0, 2.0, 0.0, 1.0], 46.0, 0.0, -24.0, 0.0),
+    ([0.0, 0.0, 2.0, 2.0, 2.0], -38.0, 0.0, 16.0, 0.0),
+    ([2.0, 0.0, 2.0, 0.0, 2.0], -31.0, 0.0, 13.0, 0.0),
+    ([2.0, 0.0, 0.0, 0.0, 0.0], 29.0, 0.0, -1.0, 0.0),
+    ([1.0, 0.0, 2.0, -2.0, 2.0], 29.0, 0.0, -12.0, 0.0),
+    ([0.0, 0.0, 2.0, 0.0, 0.0], 26.0, 0.0, -1.0, 0.0),
+    ([0.0, 0.0, 2.0, -2.0, 0.0], -22.0, 0.0, 0.0, 0.0),
+    ([-1.0, 0.0, 2.0, 0.0, 1.0], 21.0, 0.0, -10.0, 0.0),
+    ([0.0, 2.0, 0.0, 0.0, 0.0], 17.0, -0.1, 0.0, 0.0),
+    ([0.0, 2.0, 2.0, -2.0, 2.0], -16.0, 0.1, 7.0, 0.0),
+    ([-1.0, 0.0, 0.0, 2.0, 1.0], 16.0, 0.0, -8.0, 0.0),
+];
+
+// returns the passive rotation matrix about the axis (0: x, 1: y, 2: z) by the angle (rad)
+fn rotation(axis: usize, angle: f64) -> [[f64; 3]; 3] {
+    let (sin, cos) = angle.sin_cos();
+    match axis {
+        0 => [[1.0, 0.0, 0.0], [0.0, cos, sin], [0.0, -sin, cos]],
+        1 => [[cos, 0.0, -sin], [0.0, 1.0, 0.0], [sin, 0.0, cos]],
+        _ => [[cos, sin, 0.0], [-sin, cos, 0.0], [0.0, 0.0, 1.0]],
+    }
+}
+
+// returns the product of two 3 × 3 matrices
+fn multiply(first: &[[f64; 3]; 3], second: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut product = [[0.0; 3]; 3];
+    for (row, first_row) in product.iter_mut().zip(first.iter()) {
+        for (column, value) in row.iter_mut().enumerate() {
+            *value = first_row
+                .iter()
+                .zip(second.iter())
+                .map(|(first_value, second_row)| first_value * second_row[column])
+                .sum();
+        }
+    }
+    product
+}
+
+// returns the transpose of a 3 × 3 matrix (the inverse of a rotation matrix)
+fn transpose(matrix: &[[f64; 3]; 3]) -> [[f64; 3]; 3] {
+    let mut transposed = [[0.0; 3]; 3];
+    for (row, values) in matrix.iter().enumerate() {
+        for (column, value) in values.iter().enumerate() {
+            transposed[column][row] = *value;
+        }
+    }
+    transposed
+}
+
+/// Returns the rotation matrix from the True Equator, Mean Equinox frame to the Earth-fixed frame
+///
+/// The matrix R rotates TEME vectors about the z axis by the Greenwich sidereal time
+/// (polar motion is neglected), so that rₑ = R r. It can be used to transform other quantities
+/// than the position, for example a position covariance Pₑ = R P Rᵀ.
+/// The Earth-fixed velocity additionally requires the ω × rₑ term (see `Constants::propagate_frames`).
+///
+/// # Arguments
+///
+/// * `gmst` - The Greenwich sidereal time in rad
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> sgp4::Result<()> {
+/// let elements = sgp4::Elements::from_tle(
+///     Some("ISS (ZARYA)".to_owned()),
+///     "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+///     "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+/// )?;
+/// let matrix = sgp4::teme_to_ecef_matrix(sgp4::iau_epoch_to_sidereal_time(elements.epoch()));
+///
+/// // Pₑ = R P Rᵀ
+/// let covariance = [[1.0, 0.0, 0.0], [0.0, 4.0, 0.0], [0.0, 0.0, 9.0]];
+/// let mut ecef_covariance = [[0.0; 3]; 3];
+/// for row in 0..3 {
+///     for column in 0..3 {
+///         for first in 0..3 {
+///             for second in 0..3 {
+///                 ecef_covariance[row][column] +=
+///                     matrix[row][first] * covariance[first][second] * matrix[column][second];
+///             }
+///         }
+///     }
+/// }
+/// #     Ok(())
+/// # }
+/// ```
+pub fn teme_to_ecef_matrix(gmst: f64) -> [[f64; 3]; 3] {
+    // R = R₃(θ)
+    rotation(2, gmst)
+}
+
+// returns the IAU 1976 precession matrix from the mean equator and equinox of date to J2000 (FK5)
+// jd is the Julian date in terrestrial time
+fn precession_matrix(jd: f64) -> [[f64; 3]; 3] {
+    let arcsec = std::f64::consts::PI / (180.0 * 3600.0);
+
+    // T = (JD - 2451545) / 36525
+    let t = (jd - 2451545.0) / 36525.0;
+
+    // ζ = 2306.2181 T + 0.30188 T² + 0.017998 T³
+    let zeta = (2306.2181 * t + 0.30188 * t.powi(2) + 0.017998 * t.powi(3)) * arcsec;
+
+    // θ = 2004.3109 T - 0.42665 T² - 0.041833 T³
+    let theta = (2004.3109 * t - 0.42665 * t.powi(2) - 0.041833 * t.powi(3)) * arcsec;
+
+    // z = 2306.2181 T + 1.09468 T² + 0.018203 T³
+    let z = (2306.2181 * t + 1.09468 * t.powi(2) + 0.018203 * t.powi(3)) * arcsec;
+
+    // P = R₃(ζ) R₂(-θ) R₃(z)
+    multiply(
+        &multiply(&rotation(2, zeta), &rotation(1, -theta)),
+        &rotation(2, z),
+    )
+}
+
+// returns the rotation matrix from the True Equator, Mean Equinox frame at jd
+// to the mean equator and equinox of target_jd (FK5), both Julian dates in terrestrial time
+pub(crate) fn teme_to_mean_equinox_matrix(jd: f64, target_jd: f64) -> [[f64; 3]; 3] {
+    // M = Pᵀ(target) P N R₃(-Eq)
+    multiply(
+        &transpose(&precession_matrix(target_jd)),
+        &teme_to_j2000_matrix(jd),
+    )
+}
+
+// returns the rotation matrix from the True Equator, Mean Equinox frame to the J2000 (FK5) frame
+// with the IAU 1976 precession and the IAU 1980 nutation
+// jd is the Julian date in terrestrial time (UTC is accurate to a few centimeters)
+pub(crate) fn teme_to_j2000_matrix(jd: f64) -> [[f64; 3]; 3] {
+    let arcsec = std::f64::consts::PI / (180.0 * 3600.0);
+
+    // T = (JD - 2451545) / 36525
+    let t = (jd - 2451545.0) / 36525.0;
+
+    // Delaunay arguments in arcsec (r = 1296000")
+    // l = 485866.733 + (1325 r + 715922.633) T + 31.310 T² + 0.064 T³
+    // l' = 1287099.804 + (99 r + 1292581.224) T - 0.577 T² - 0.012 T³
+    // F = 335778.877 + (1342 r + 295263.137) T - 13.257 T² + 0.011 T³
+    // D = 1072261.307 + (1236 r + 1105601.328) T - 6.891 T² + 0.019 T³
+    // Ω = 450160.280 - (5 r + 482890.539) T + 7.455 T² + 0.008 T³
+    let arguments = [
+        485866.733 + (1325.0 * 1296000.0 + 715922.633) * t + 31.310 * t.powi(2) + 0.064 * t.powi(3),
+        1287099.804 + (99.0 * 1296000.0 + 1292581.224) * t - 0.577 * t.powi(2) - 0.012 * t.powi(3),
+        335778.877 + (1342.0 * 1296000.0 + 295263.137) * t - 13.257 * t.powi(2) + 0.011 * t.powi(3),
+        1072261.307 + (1236.0 * 1296000.0 + 1105601.328) * t - 6.891 * t.powi(2)
+            + 0.019 * t.powi(3),
+        450160.280 - (5.0 * 1296000.0 + 482890.539) * t + 7.455 * t.powi(2) + 0.008 * t.powi(3),
+    ];
+
+    // Δψ = Σᵢ (Aᵢ + Bᵢ T) sin aᵢ, Δε = Σᵢ (Cᵢ + Dᵢ T) cos aᵢ
+    // aᵢ = kᵢ₁ l + kᵢ₂ l' + kᵢ₃ F + kᵢ₄ D + kᵢ₅ Ω
+    let (mut delta_psi, mut delta_epsilon) = (0.0, 0.0);
+    for (multipliers, a, b, c, d) in NUTATION_TERMS.iter() {
+        let argument = multipliers
+            .iter()
+            .zip(arguments.iter())
+            .map(|(multiplier, argument)| multiplier * argument)
+            .sum::<f64>()
+            * arcsec;
+        delta_psi += (a + b * t) * argument.sin();
+        delta_epsilon += (c + d * t) * argument.cos();
+    }
+    delta_psi *= 1.0e-4 * arcsec;
+    delta_epsilon *= 1.0e-4 * arcsec;
+
+    // ε̄ = 84381.448 - 46.8150 T - 0.00059 T² + 0.001813 T³
+    let mean_obliquity =
+        (84381.448 - 46.8150 * t - 0.00059 * t.powi(2) + 0.001813 * t.powi(3)) * arcsec;
+    let precession = precession_matrix(jd);
+
+    // N = R₁(-ε̄) R₃(Δψ) R₁(ε̄ + Δε)
+    let nutation = multiply(
+        &multiply(&rotation(0, -mean_obliquity), &rotation(2, delta_psi)),
+        &rotation(0, mean_obliquity + delta_epsilon),
+    );
+
+    // the equation of the equinoxes (without the kinematic terms, as in the TEME definition)
+    // Eq = Δψ cos ε̄
+    let equation_of_equinoxes = delta_psi * mean_obliquity.cos();
+
+    // M = P N R₃(-Eq)
+    multiply(
+        &multiply(&precession, &nutation),
+        &rotation(2, -equation_of_equinoxes),
+    )
+}
+
+/// Returns the inclination of a sun-synchronous orbit
+///
+/// A sun-synchronous orbit's ascending node precesses eastward at the Earth's mean orbital rate
+/// around the Sun (2π rad per tropical year, about 0.9856° per day).
+/// The first-order J₂ secular nodal rate Ω̇ = -³/₂ n J₂ (aₑ / p)² cos I,
+/// with p = a (1 - e²) and n = (μ / a³)¹ᐟ², is solved for I.
+///
+/// Returns NaN if no inclination yields the target rate (semi-major axes above about 12350 km for circular orbits).
+///
+/// # Arguments
+///
+/// * `semi_major_axis_km` - The semi-major axis in km
+/// * `eccentricity` - The eccentricity, in the range [0, 1[
+/// * `geopotential` - The model of Earth gravity providing aₑ, kₑ and J₂
+///
+/// # Example
+///
+/// ```
+/// let inclination = sgp4::sun_synchronous_inclination(6378.137 + 700.0, 0.0, &sgp4::WGS84);
+/// assert!((inclination * (180.0 / std::f64::consts::PI) - 98.19).abs() < 0.01);
+/// ```
+pub fn sun_synchronous_inclination(
+    semi_major_axis_km: f64,
+    eccentricity: f64,
+    geopotential: &Geopotential,
+) -> f64 {
+    // Ω̇ₛ = 2π / (365.2421897 × 24 × 60) rad.min⁻¹
+    let target_rate = 2.0 * std::f64::consts::PI / (365.2421897 * 24.0 * 60.0);
+
+    // a in earth radii
+    let a = semi_major_axis_km / geopotential.ae;
+
+    // n = kₑ / a³ᐟ² in rad.min⁻¹
+    let n = geopotential.ke / a.powf(1.5);
+
+    // p = a (1 - e²)
+    let p = a * (1.0 - eccentricity.powi(2));
+
+    // cos I = - Ω̇ₛ / (³/₂ n J₂ / p²)
+    (-target_rate / (1.5 * n * geopotential.j2 / p.powi(2))).acos()
+}
+
+/// Solves Kepler's equation E - e sin E = M for the eccentric anomaly
+///
+/// The mean anomaly is reduced to [0, 2π[ and Newton-Raphson iterations start from M
+/// (or from π if e > 0.8, which converges for every eccentricity below 1). The iterations stop when
+/// the correction is smaller than 10⁻¹² rad. The returned eccentric anomaly is in the same revolution as M.
+///
+/// SGP4 itself solves a modified equation for E + ω that includes the long-period periodic effects,
+/// see `Constants::propagate_verbose`.
+///
+/// # Arguments
+///
+/// * `mean_anomaly` - The mean anomaly M in rad
+/// * `eccentricity` - The eccentricity e, in the range [0, 1[
+///
+/// # Example
+///
+/// ```
+/// let eccentric_anomaly = sgp4::kepler_solve(1.0, 0.7);
+/// assert!((eccentric_anomaly - 0.7 * eccentric_anomaly.sin() - 1.0).abs() < 1.0e-12);
+/// ```
+pub fn kepler_solve(mean_anomaly: f64, eccentricity: f64) -> f64 {
+    let reduced_mean_anomaly = mean_anomaly.rem_euclid(2.0 * std::f64::consts::PI);
+    let mut eccentric_anomaly = if eccentricity > 0.8 {
+        std::f64::consts::PI
+    } else {
+        reduced_mean_anomaly
+    };
+    for _ in 0..50 {
+        //       E - e sin E - M
+        // ΔE = ---------------
+        //         1 - e cos E
+        let delta =
+            (eccentric_anomaly - eccentricity * eccentric_anomaly.sin() - reduced_mean_anomaly)
+                / (1.0 - eccentricity * eccentric_anomaly.cos());
+        eccentric_anomaly -= delta;
+        if delta.abs() < 1.0e-12 {
+            break;
+        }
+    }
+    eccentric_anomaly + (mean_anomaly - reduced_mean_anomaly)
+}
+
+/// Converts an eccentric anomaly to a true anomaly
+///
+/// The half-angle expression ν = 2 tan⁻¹((1 + e)¹ᐟ² sin(E / 2), (1 - e)¹ᐟ² cos(E / 2))
+/// is used with a two-argument arctangent, which is accurate at every anomaly, including E = π,
+/// and keeps ν in the same revolution as E.
+///
+/// # Arguments
+///
+/// * `eccentric_anomaly` - The eccentric anomaly E in rad
+/// * `eccentricity` - The eccentricity e, in the range [0, 1[
+///
+/// # Example
+///
+/// ```
+/// let true_anomaly = sgp4::eccentric_to_true(std::f64::consts::FRAC_PI_2, 0.5);
+/// assert!((true_anomaly.cos() + 0.5).abs() < 1.0e-12);
+/// ```
+pub fn eccentric_to_true(eccentric_anomaly: f64, eccentricity: f64) -> f64 {
+    // the revolution of E is kept by reducing E / 2 to [-π/2, π/2]
+    let revolutions = (eccentric_anomaly / (2.0 * std::f64::consts::PI)).round();
+    let half_angle = (eccentric_anomaly - revolutions * 2.0 * std::f64::consts::PI) / 2.0;
+
+    // ν = 2 tan⁻¹((1 + e)¹ᐟ² sin(E / 2), (1 - e)¹ᐟ² cos(E / 2))
+    2.0 * ((1.0 + eccentricity).sqrt() * half_angle.sin())
+        .atan2((1.0 - eccentricity).sqrt() * half_angle.cos())
+        + revolutions * 2.0 * std::f64::consts::PI
+}
+
 /// Converts an epoch to sidereal time using the AFSPC expression
 ///
 /// This function should be used if compatibility with the AFSPC implementation is needed.
@@ -86,3 +607,154 @@ pub fn afspc_epoch_to_sidereal_time(epoch: f64) -> f64 {
         + d1970.powi(2) * 5.07551419432269442e-15)
         .rem_euclid(2.0 * std::f64::consts::PI)
 }
+
+// astronomical unit in km
+const ASTRONOMICAL_UNIT: f64 = 149597870.7;
+
+// nominal solar radius in km (IAU 2015 Resolution B3)
+pub(crate) const SUN_RADIUS: f64 = 695700.0;
+
+// returns the position of the Sun relative to the Earth's center in km
+// with the low-precision solar ephemeris from the Astronomical Almanac
+pub(crate) fn epoch_to_sun_position(epoch: f64) -> [f64; 3] {
+    // c₂₀₀₀ = y₂₀₀₀ / 100
+    let c2000 = epoch / 100.0;
+
+    // Mₛ = 357.5291092 + 35999.05034 c₂₀₀₀
+    let mean_anomaly = (357.5291092 + 35999.05034 * c2000) * (std::f64::consts::PI / 180.0);
+
+    // R = 1.000140612 - 0.016708617 cos Mₛ - 0.000139589 cos 2 Mₛ in AU
+    let distance =
+        (1.000140612 - 0.016708617 * mean_anomaly.cos() - 0.000139589 * (2.0 * mean_anomaly).cos())
+            * ASTRONOMICAL_UNIT;
+    let direction = epoch_to_sun_direction(epoch);
+    [
+        direction[0] * distance,
+        direction[1] * distance,
+        direction[2] * distance,
+    ]
+}
+
+/// Converts an epoch to the unit vector pointing from the Earth's center to the Sun
+///
+/// The low-precision solar ephemeris from the Astronomical Almanac is used.
+/// The direction is given in the mean equator and equinox of date frame,
+/// which is within a few arcminutes of the True Equator, Mean Equinox frame used by SGP4.
+///
+/// # Arguments
+///
+/// * `epoch` - Years since UTC 1 January 2000 12h00
+pub fn epoch_to_sun_direction(epoch: f64) -> [f64; 3] {
+    // c₂₀₀₀ = y₂₀₀₀ / 100
+    let c2000 = epoch / 100.0;
+
+    // λₘ = 280.460 + 36000.771 c₂₀₀₀
+    let mean_longitude = (280.460 + 36000.771 * c2000) * (std::f64::consts::PI / 180.0);
+
+    // Mₛ = 357.5291092 + 35999.05034 c₂₀₀₀
+    let mean_anomaly = (357.5291092 + 35999.05034 * c2000) * (std::f64::consts::PI / 180.0);
+
+    // λₑ = λₘ + 1.914666471 sin Mₛ + 0.019994643 sin 2 Mₛ
+    let ecliptic_longitude = mean_longitude
+        + (1.914666471 * mean_anomaly.sin() + 0.019994643 * (2.0 * mean_anomaly).sin())
+            * (std::f64::consts::PI / 180.0);
+
+    // ε = 23.439291 - 0.0130042 c₂₀₀₀
+    let obliquity = (23.439291 - 0.0130042 * c2000) * (std::f64::consts::PI / 180.0);
+
+    // ŝ = [cos λₑ, cos ε sin λₑ, sin ε sin λₑ]
+    [
+        ecliptic_longitude.cos(),
+        obliquity.cos() * ecliptic_longitude.sin(),
+        obliquity.sin() * ecliptic_longitude.sin(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anomalies() {
+        for eccentricity in &[0.0, 0.1, 0.5, 0.9, 0.99] {
+            for index in -20..=20 {
+                let mean_anomaly = index as f64 * 0.4;
+                let eccentric_anomaly = kepler_solve(mean_anomaly, *eccentricity);
+                assert!(
+                    (eccentric_anomaly - eccentricity * eccentric_anomaly.sin() - mean_anomaly)
+                        .abs()
+                        < 1.0e-12
+                );
+                let true_anomaly = eccentric_to_true(eccentric_anomaly, *eccentricity);
+
+                // cos ν = (cos E - e) / (1 - e cos E)
+                assert!(
+                    (true_anomaly.cos()
+                        - (eccentric_anomaly.cos() - eccentricity)
+                            / (1.0 - eccentricity * eccentric_anomaly.cos()))
+                    .abs()
+                        < 1.0e-12
+                );
+
+                // ν and E are in the same half revolution
+                assert!((true_anomaly - eccentric_anomaly).abs() < std::f64::consts::PI);
+            }
+        }
+        assert_eq!(eccentric_to_true(0.0, 0.5), 0.0);
+        assert!(
+            (eccentric_to_true(std::f64::consts::PI, 0.5) - std::f64::consts::PI).abs() < 1.0e-12
+        );
+    }
+
+    #[test]
+    fn test_precession_matrix() {
+        // IAU 1976 precession from the mean equinox of B1950.0 to J2000.0 (Lieske 1979)
+        let matrix = precession_matrix(2433282.4235);
+        for (row, expected_row) in matrix.iter().zip(
+            [
+                [0.9999257079523629, -0.0111789381377700, -0.0048590038153592],
+                [0.0111789381264276, 0.9999375133499888, -0.0000271625947142],
+                [0.0048590038414544, -0.0000271579262585, 0.9999881946023742],
+            ]
+            .iter(),
+        ) {
+            for (value, expected) in row.iter().zip(expected_row.iter()) {
+                assert!((value - expected).abs() < 1.0e-9);
+            }
+        }
+        assert_eq!(
+            precession_matrix(2451545.0),
+            [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]
+        );
+    }
+
+    #[test]
+    fn test_teme_to_j2000_matrix() {
+        // Vallado, Crawford, Hujsak and Kelso (2006), "Revisiting Spacetrack Report #3", AIAA 2006-6753
+        // 2004 April 6 07:51:28.386009 UTC, TT = UTC + 64.184 s
+        let matrix = teme_to_j2000_matrix(
+            2453101.5 + (7.0 * 3600.0 + 51.0 * 60.0 + 28.386009 + 64.184) / 86400.0,
+        );
+        let rotate = |vector: [f64; 3]| {
+            [
+                matrix[0][0] * vector[0] + matrix[0][1] * vector[1] + matrix[0][2] * vector[2],
+                matrix[1][0] * vector[0] + matrix[1][1] * vector[1] + matrix[1][2] * vector[2],
+                matrix[2][0] * vector[0] + matrix[2][1] * vector[1] + matrix[2][2] * vector[2],
+            ]
+        };
+        let position = rotate([5094.18016210, 6127.64465950, 6380.34453270]);
+        let velocity = rotate([-4.746131487, 0.785818041, 5.531931288]);
+        for (value, expected) in position
+            .iter()
+            .zip([5102.509600, 6123.011520, 6378.136300].iter())
+        {
+            assert!((value - expected).abs() < 1.0e-3);
+        }
+        for (value, expected) in velocity
+            .iter()
+            .zip([-4.743220157, 0.790536497, 5.533755727].iter())
+        {
+            assert!((value - expected).abs() < 1.0e-6);
+        }
+    }
+}
@@ -4,11 +4,14 @@ use crate::propagator;
 
 pub(crate) fn constants<'a>(
     geopotential: &'a model::Geopotential,
+    epoch: f64,
+    sidereal_time_0: f64,
     drag_term: f64,
     orbit_0: propagator::Orbit,
     p1: f64,
     a0: f64,
     s: f64,
+    atmospheric_s: Option<f64>,
     xi: f64,
     eta: f64,
     c1: f64,
@@ -26,6 +29,9 @@ pub(crate) fn constants<'a>(
 ) -> propagator::Constants<'a> {
     propagator::Constants {
         geopotential: geopotential,
+        epoch: epoch,
+        sidereal_time_0: sidereal_time_0,
+        drag_term: drag_term,
 
         // Ω̇ = p₁₄
         right_ascension_dot: p14,
@@ -39,6 +45,7 @@ pub(crate) fn constants<'a>(
         c4: c4,
         k0: k0,
         k1: k1,
+        atmospheric_s: atmospheric_s,
         method: propagator::Method::NearEarth {
             a0: a0,
 
@@ -151,6 +158,8 @@ pub(crate) fn constants<'a>(
             },
         },
         orbit_0: orbit_0,
+        kepler_solver: propagator::KeplerSolver::NewtonRaphson,
+        short_period_corrections: true,
     }
 }
 
@@ -167,6 +176,7 @@ impl<'a> propagator::Constants<'a> {
         t: f64,
         p22: f64,
         p23: f64,
+        warnings: Option<&mut Vec<propagator::Warning>>,
     ) -> gp::Result<(propagator::Orbit, f64, f64, f64, f64, f64, f64)> {
         // p₂₄ = M₀ + Ṁ t
         let p24 = self.orbit_0.mean_anomaly + self.mean_anomaly_dot * t;
@@ -221,6 +231,12 @@ impl<'a> propagator::Constants<'a> {
         if p27 >= 1.0 || p27 < -0.001 {
             Err(gp::Error::new("diverging eccentricity".to_owned()))
         } else {
+            if p27 < 1.0e-6 {
+                if let Some(warnings) = warnings {
+                    warnings.push(propagator::Warning::ClampedEccentricity { eccentricity: p27 });
+                }
+            }
+
             // e = │ 10⁻⁶ if p₂₇ < 10⁻⁶
             //     │ p₂₇  otherwise
             let eccentricity = p27.max(1.0e-6);
@@ -1,3 +1,4 @@
+use crate::deep_space;
 use crate::model;
 use crate::third_body;
 
@@ -12,7 +13,232 @@ pub struct Prediction {
     pub velocity: [f64; 3],
 }
 
+/// A position in geodetic coordinates, relative to the WGS84 ellipsoid unless stated otherwise
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Geodetic {
+    /// Geodetic latitude in rad, positive north
+    pub latitude: f64,
+
+    /// Longitude in rad, positive east
+    pub longitude: f64,
+
+    /// Height above the ellipsoid in km
+    pub altitude: f64,
+}
+
+/// A ground observer position given by its Earth-fixed coordinates
+///
+/// The three components (x, y, z) are given in km in the Earth-fixed frame
+/// used by [Constants::propagate_frames](struct.Constants.html#method.propagate_frames).
+/// A `Geodetic` observer can be converted to an `EcefObserver` with `into`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EcefObserver(pub [f64; 3]);
+
+/// The direction and distance of a satellite seen from a ground observer
+///
+/// See [Constants::look_angles](struct.Constants.html#method.look_angles).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LookAngles {
+    /// Angle in rad measured clockwise from the north, in the range [0, 2π[
+    pub azimuth: f64,
+
+    /// Angle in rad above the local horizontal plane, in the range [-π/2, π/2]
+    pub elevation: f64,
+
+    /// Distance between the observer and the satellite in km
+    pub range: f64,
+
+    /// Rate of change of the azimuth in rad.s⁻¹
+    ///
+    /// The azimuth rate is singular at the zenith (elevation ≈ π/2).
+    /// The horizontal distance is bounded below by 1 mm, so the rate remains finite but can be very large.
+    pub azimuth_rate: f64,
+
+    /// Rate of change of the elevation in rad.s⁻¹
+    pub elevation_rate: f64,
+}
+
+/// A time window during which a satellite is above an observer's elevation mask
+///
+/// See [Constants::passes](struct.Constants.html#method.passes).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pass {
+    /// The time at which the satellite rises above the mask in minutes since epoch
+    pub rise: f64,
+
+    /// The time at which the satellite sets below the mask in minutes since epoch
+    pub set: f64,
+
+    /// The time of maximum elevation in minutes since epoch
+    pub culmination: f64,
+
+    /// The maximum elevation in rad
+    pub max_elevation: f64,
+}
+
+/// A labeled SGP4 prediction, convenient for tabular exports
+///
+/// See [Constants::propagate_record](struct.Constants.html#method.propagate_record).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Record {
+    /// The number of minutes since epoch
+    pub t: f64,
+
+    /// The UTC timestamp of the prediction (epoch + t), rounded to the microsecond
+    pub datetime: chrono::naive::NaiveDateTime,
+
+    /// The three position components (x, y, z) in km (True Equator, Mean Equinox frame)
+    pub position: [f64; 3],
+
+    /// The three velocity components (x, y, z) in km.s⁻¹ (True Equator, Mean Equinox frame)
+    pub velocity: [f64; 3],
+}
+
+/// A non-fatal degradation of an SGP4 prediction
+///
+/// See [Constants::propagate_checked](struct.Constants.html#method.propagate_checked).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Warning {
+    /// The mean eccentricity at the prediction time was below 10⁻⁶ and was clamped to 10⁻⁶
+    ClampedEccentricity {
+        /// The eccentricity before clamping
+        eccentricity: f64,
+    },
+
+    /// The inclination is so close to 180° that the 1 + cos I divisor of the long-period terms was clamped to 1.5 × 10⁻¹²
+    SingularInclination {
+        /// The inclination in rad
+        inclination: f64,
+    },
+
+    /// Kepler's equation did not converge to 10⁻¹² rad within 10 iterations
+    KeplerNotConverged {
+        /// The residual of Kepler's equation for E + ω in rad after the last iteration
+        residual: f64,
+    },
+
+    /// The prediction time is outside the range returned by `Constants::recommended_time_range`
+    OutsideRecommendedTimeRange {
+        /// The number of minutes since epoch
+        t: f64,
+    },
+}
+
+/// Intermediate quantities calculated during SGP4 propagation
+///
+/// They are calculated from the mean elements at the prediction time, before the short-period corrections.
+pub struct DerivedQuantities {
+    /// The semi-latus rectum pₗ in km
+    pub semi_latus_rectum: f64,
+
+    /// The norm of the specific angular momentum (kₑ² pₗ)¹ᐟ² in km².s⁻¹
+    pub specific_angular_momentum: f64,
+}
+
+/// The Brouwer mean orbital elements at epoch expressed with a semi-major axis
+///
+/// See [Constants::mean_elements](struct.Constants.html#method.mean_elements).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeanElements {
+    /// Brouwer semi-major axis a₀" in km
+    pub semi_major_axis: f64,
+
+    /// Shape of the orbit
+    pub eccentricity: f64,
+
+    /// Angle between the equator and the orbit plane in rad
+    pub inclination: f64,
+
+    /// Angle between vernal equinox and the point where the orbit crosses the equatorial plane in rad
+    pub right_ascension: f64,
+
+    /// Angle between the ascending node and the orbit's point of closest approach to the earth in rad
+    pub argument_of_perigee: f64,
+
+    /// Angle of the satellite location measured from perigee in rad
+    pub mean_anomaly: f64,
+}
+
+/// The mean eccentricity and anomalies at a given time
+///
+/// SGP4 clamps mean eccentricities below 10⁻⁶ to 10⁻⁶, and the perigee of such an orbit is not defined.
+/// For these circular orbits, the anomalies are replaced with arguments of latitude,
+/// measured from the ascending node, and the argument of perigee is set to zero.
+///
+/// See [Constants::anomalies](struct.Constants.html#method.anomalies).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anomalies {
+    /// Mean eccentricity, including the solar and lunar long-period effects for deep space orbits
+    pub eccentricity: f64,
+
+    /// Whether the mean eccentricity was clamped to 10⁻⁶ and the angles are arguments of latitude
+    pub circular: bool,
+
+    /// Mean argument of perigee ω in rad in the range [0, 2π[, or zero if `circular` is true
+    pub argument_of_perigee: f64,
+
+    /// Mean anomaly M in rad in the range [0, 2π[, or mean argument of latitude ω + M if `circular` is true
+    pub mean_anomaly: f64,
+
+    /// True anomaly ν in rad in the range [0, 2π[, or true argument of latitude ω + ν if `circular` is true
+    pub true_anomaly: f64,
+}
+
+/// The secular rates of the angular elements
+///
+/// See [Constants::secular_rates](struct.Constants.html#method.secular_rates).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SecularRates {
+    /// Secular rate of the right ascension of the ascending node Ω̇ in rad.min⁻¹
+    pub right_ascension_dot: f64,
+
+    /// Secular rate of the argument of perigee ω̇ in rad.min⁻¹
+    pub argument_of_perigee_dot: f64,
+
+    /// Secular rate of the mean anomaly Ṁ in rad.min⁻¹, including the Brouwer mean motion n₀"
+    pub mean_anomaly_dot: f64,
+}
+
+/// The secular drift of the orbital elements over a duration
+///
+/// See [Constants::elements_drift_report](struct.Constants.html#method.elements_drift_report).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ElementsDrift {
+    /// The duration of the report in min
+    pub duration: f64,
+
+    /// Drift of the right ascension of the ascending node ΔΩ in rad
+    pub right_ascension: f64,
+
+    /// Drift of the argument of perigee Δω in rad
+    pub argument_of_perigee: f64,
+
+    /// Drift of the mean anomaly ΔM in rad, including the mean motion
+    pub mean_anomaly: f64,
+
+    /// Solar and lunar drift of the eccentricity Δe (zero for near earth orbits)
+    pub eccentricity: f64,
+
+    /// Solar and lunar drift of the inclination ΔI in rad (zero for near earth orbits)
+    pub inclination: f64,
+}
+
+/// A flat copy of every quantity stored in a propagator
+///
+/// See [Constants::debug_dump](struct.Constants.html#method.debug_dump).
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ConstantsSnapshot {
+    /// The propagation method and its variants, for example "deep space, resonant (half day)"
+    pub method: String,
+
+    /// The stored quantities indexed by name, for example "c1" or "d2201"
+    ///
+    /// The third body coefficients of deep space orbits are prefixed with "solar." or "lunar.".
+    pub values: std::collections::BTreeMap<String, f64>,
+}
+
 /// The Brouwer orbital elements
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Orbit {
     /// Angle between the equator and the orbit plane in rad
     pub inclination: f64,
@@ -33,11 +259,233 @@ pub struct Orbit {
     pub mean_motion: f64,
 }
 
+/// A propagation time relative to the epoch
+///
+/// SGP4 expects times in minutes since epoch. This type makes the unit explicit
+/// and can be created from minutes, seconds, days or durations.
+/// See [Constants::propagate_time](struct.Constants.html#method.propagate_time).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct PropagationTime {
+    pub(crate) minutes: f64,
+}
+
+/// An absolute UTC instant used as the epoch of orbital elements
+///
+/// SGP4 represents epochs as a number of years since UTC 1 January 2000 12h00 (J2000).
+/// This type performs the conversion from Julian dates, modified Julian dates or timestamps.
+/// See [Constants::from_orbit](struct.Constants.html#method.from_orbit).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Epoch {
+    pub(crate) years_since_j2000: f64,
+}
+
+/// Eccentricity above which an orbit is classified as highly elliptical
+pub const HEO_MIN_ECCENTRICITY: f64 = 0.25;
+
+/// Mean altitude in km below which an orbit is classified as low Earth
+///
+/// The mean altitude is the semi-major axis minus the equatorial radius.
+pub const LEO_MAX_ALTITUDE: f64 = 2000.0;
+
+/// Semi-major axis in km of a geosynchronous orbit
+pub const GEO_SEMI_MAJOR_AXIS: f64 = 42164.0;
+
+/// Maximum distance in km between the semi-major axis and `GEO_SEMI_MAJOR_AXIS` for geosynchronous orbits
+pub const GEO_SEMI_MAJOR_AXIS_TOLERANCE: f64 = 500.0;
+
+/// Geocentric distance in km above which a prediction is rejected as non-physical
+pub const MAX_GEOCENTRIC_RADIUS: f64 = 10.0 * GEO_SEMI_MAJOR_AXIS;
+
+/// Maximum number of samples taken by the methods that scan a time window
+///
+/// Windows that would require more samples are rejected with an error instead of
+/// running (or allocating) without bound.
+pub const MAX_SAMPLES: usize = 10_000_000;
+
+// returns the number of steps of at most step minutes needed to cover span minutes
+// span must be finite and non-negative, and the count must not exceed MAX_SAMPLES
+pub(crate) fn step_count(span: f64, step: f64) -> crate::gp::Result<usize> {
+    if !span.is_finite() || span < 0.0 {
+        return Err(crate::gp::Error::new(
+            "the time window must be finite and non-negative".to_owned(),
+        ));
+    }
+    let steps = (span / step).ceil();
+    if steps > MAX_SAMPLES as f64 {
+        Err(crate::gp::Error::new(format!(
+            "the time window requires more than {} samples",
+            MAX_SAMPLES
+        )))
+    } else {
+        Ok(steps as usize)
+    }
+}
+
+/// Orbit regime derived from the epoch semi-major axis and eccentricity
+///
+/// See [Constants::orbit_class](struct.Constants.html#method.orbit_class) for the classification rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrbitClass {
+    /// Low Earth orbit, with a mean altitude below `LEO_MAX_ALTITUDE`
+    Leo,
+
+    /// Medium Earth orbit, between low Earth orbits and the geosynchronous band
+    Meo,
+
+    /// Geosynchronous orbit, with a semi-major axis within `GEO_SEMI_MAJOR_AXIS_TOLERANCE` of `GEO_SEMI_MAJOR_AXIS`
+    Geo,
+
+    /// Near-circular orbit above the geosynchronous band
+    BeyondGeo,
+
+    /// Highly elliptical orbit, with an eccentricity of at least `HEO_MIN_ECCENTRICITY`
+    Heo,
+}
+
+/// The code path used by a propagator
+///
+/// See [Constants::propagation_method](struct.Constants.html#method.propagation_method).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PropagationMethod {
+    /// Near earth model (SGP4), for orbital periods below 225 minutes
+    NearEarth,
+
+    /// Deep space model (SDP4) with solar and lunar perturbations, without resonance terms
+    DeepSpaceNonResonant,
+
+    /// Deep space model with the one-day (geosynchronous) resonance terms
+    DeepSpaceOneDay,
+
+    /// Deep space model with the half-day (Molniya) resonance terms
+    DeepSpaceHalfDay,
+}
+
+/// Resonance model used by a deep space propagator
+///
+/// See [Constants::perturbation_profile](struct.Constants.html#method.perturbation_profile).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResonanceKind {
+    /// No resonance terms are integrated (near earth and non-resonant deep space orbits)
+    None,
+
+    /// One-day (geosynchronous) resonance
+    OneDay,
+
+    /// Half-day (Molniya) resonance
+    HalfDay,
+}
+
+/// The perturbations modelled by a propagator
+///
+/// The J₂, J₃ and J₄ zonal harmonics are always modelled and are therefore not listed.
+/// See [Constants::perturbation_profile](struct.Constants.html#method.perturbation_profile).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PerturbationProfile {
+    /// Whether atmospheric drag is modelled (B* ≠ 0)
+    pub drag: bool,
+
+    /// Whether the solar and lunar perturbations are modelled (deep space orbits)
+    pub third_body: bool,
+
+    /// The Earth gravity resonance model
+    pub resonance: ResonanceKind,
+}
+
+/// The atmospheric density parameters used by the drag model
+///
+/// See [Constants::atmospheric_parameters](struct.Constants.html#method.atmospheric_parameters).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtmosphericParameters {
+    /// The density function parameter s in earth radii, measured from the Earth center
+    pub s: f64,
+
+    /// The (q₀ - s)⁴ term in earth radii⁴, with q₀ = 120 km / aₑ + 1
+    pub q0_minus_s_pow4: f64,
+}
+
+/// The branches taken by the deep space mean elements calculation at a given time
+///
+/// See [Constants::deep_space_diagnostics](struct.Constants.html#method.deep_space_diagnostics).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeepSpaceDiagnostics {
+    /// The perturbed mean inclination I in rad that selects the right ascension formulation
+    pub inclination: f64,
+
+    /// Whether Ω and ω were calculated with the Lyddane modification (I < 0.2 rad)
+    pub lyddane: bool,
+
+    /// Whether the mean eccentricity was clamped to 10⁻⁶ before adding the third body periodics
+    pub clamped_eccentricity: bool,
+}
+
+/// The reference frame of the states written in an Orbit Ephemeris Message
+///
+/// See [Constants::write_oem](struct.Constants.html#method.write_oem).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OemFrame {
+    /// True Equator, Mean Equinox frame used by SGP4 (REF_FRAME = TEME)
+    Teme,
+
+    /// Mean equator and equinox of J2000 (REF_FRAME = EME2000), see `Prediction::to_j2000`
+    J2000,
+}
+
+/// The metadata written in an Orbit Ephemeris Message
+///
+/// See [Constants::write_oem](struct.Constants.html#method.write_oem).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OemMetadata {
+    /// The UTC date at which the message is created (CREATION_DATE)
+    pub creation_date: chrono::naive::NaiveDateTime,
+
+    /// The creating agency or operator (ORIGINATOR)
+    pub originator: String,
+
+    /// The name of the object (OBJECT_NAME), for example the TLE title
+    pub object_name: String,
+
+    /// The international designator of the object (OBJECT_ID), for example "1998-067A"
+    pub object_id: String,
+
+    /// The reference frame of the states (REF_FRAME)
+    pub frame: OemFrame,
+}
+
+/// Illumination of the satellite by the Sun
+///
+/// See [Constants::eclipse_state](struct.Constants.html#method.eclipse_state).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EclipseState {
+    /// The Sun disk is not occulted by the Earth
+    Sunlit,
+
+    /// The Sun disk is partially occulted by the Earth
+    Penumbra,
+
+    /// The Sun disk is entirely occulted by the Earth
+    Umbra,
+}
+
+/// Method used to solve Kepler's equation during propagation
+///
+/// See [Constants::with_kepler_solver](struct.Constants.html#method.with_kepler_solver).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum KeplerSolver {
+    /// Newton-Raphson iterations with steps clamped to [-0.95, 0.95] rad, as in the reference implementation
+    #[default]
+    NewtonRaphson,
+
+    /// Laguerre-Conway iterations, which converge in fewer steps for highly eccentric orbits
+    LaguerreConway,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
 pub(crate) enum Elliptic {
     No {},
     Yes { k11: f64, k12: f64, k13: f64 },
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 pub(crate) enum HighAltitude {
     No {},
     Yes {
@@ -54,6 +502,7 @@ pub(crate) enum HighAltitude {
     },
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 pub(crate) enum Resonance {
     OneDay {
         dr1: f64,
@@ -75,6 +524,7 @@ pub(crate) enum Resonance {
     },
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 pub(crate) enum Resonant {
     No {
         a0: f64,
@@ -87,6 +537,7 @@ pub(crate) enum Resonant {
     },
 }
 
+#[derive(serde::Serialize, serde::Deserialize)]
 pub(crate) enum Method {
     NearEarth {
         a0: f64,
@@ -112,8 +563,38 @@ pub(crate) enum Method {
 /// They are not mutated during propagation, which means they can
 /// be used by different threads in parallel
 /// (for example to generate predictions at different times).
+///
+/// Constants can be serialized to cache propagators and skip the initialization on reload.
+/// Only propagators that use the `WGS84` or `WGS72` geopotential models can be deserialized.
+/// Bit-exact round trips require a lossless float format (for example `serde_json`
+/// with its `float_roundtrip` feature).
+///
+/// # Example
+///
+/// ```
+/// # fn main() -> sgp4::Result<()> {
+/// let constants = sgp4::Constants::from_elements(
+///     &sgp4::Elements::from_tle(
+///         Some("ISS (ZARYA)".to_owned()),
+///         "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+///         "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+///     )?,
+/// )?;
+/// let cached = serde_json::to_string(&constants)?;
+/// let reloaded: sgp4::Constants = serde_json::from_str(&cached)?;
+/// let position = constants.propagate(60.0)?.position;
+/// let reloaded_position = reloaded.propagate(60.0)?.position;
+/// assert!((position[0] - reloaded_position[0]).abs() < 1.0e-9);
+/// #     Ok(())
+/// # }
+/// ```
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Constants<'a> {
+    #[serde(with = "model::geopotential_reference")]
     pub(crate) geopotential: &'a model::Geopotential,
+    pub(crate) epoch: f64,
+    pub(crate) sidereal_time_0: f64,
+    pub(crate) drag_term: f64,
     pub(crate) right_ascension_dot: f64,
     pub(crate) argument_of_perigee_dot: f64,
     pub(crate) mean_anomaly_dot: f64,
@@ -121,6 +602,24 @@ pub struct Constants<'a> {
     pub(crate) c4: f64,
     pub(crate) k0: f64,
     pub(crate) k1: f64,
+    #[serde(default)]
+    pub(crate) atmospheric_s: Option<f64>,
     pub(crate) method: Method,
     pub(crate) orbit_0: Orbit,
+    #[serde(default)]
+    pub(crate) kepler_solver: KeplerSolver,
+    #[serde(default = "default_short_period_corrections")]
+    pub(crate) short_period_corrections: bool,
+}
+
+fn default_short_period_corrections() -> bool {
+    true
+}
+
+/// A propagator that keeps the deep space resonance integrator state between predictions
+///
+/// See [StatefulPropagator::step_to](struct.StatefulPropagator.html#method.step_to).
+pub struct StatefulPropagator<'a> {
+    pub(crate) constants: Constants<'a>,
+    pub(crate) state: Option<deep_space::ResonanceState>,
 }
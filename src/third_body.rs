@@ -1,3 +1,4 @@
+#[derive(serde::Serialize, serde::Deserialize)]
 pub(crate) struct Perturbations {
     kx0: f64,
     kx1: f64,
@@ -278,4 +279,29 @@ impl Perturbations {
             self.kx10 * fx2 + self.kx11 * fx3,
         )
     }
+
+    // adds the coefficients to values, with keys prefixed by prefix (for example "solar.kx0")
+    pub(crate) fn insert_values(
+        &self,
+        prefix: &str,
+        values: &mut std::collections::BTreeMap<String, f64>,
+    ) {
+        for (name, value) in &[
+            ("kx0", self.kx0),
+            ("kx1", self.kx1),
+            ("kx2", self.kx2),
+            ("kx3", self.kx3),
+            ("kx4", self.kx4),
+            ("kx5", self.kx5),
+            ("kx6", self.kx6),
+            ("kx7", self.kx7),
+            ("kx8", self.kx8),
+            ("kx9", self.kx9),
+            ("kx10", self.kx10),
+            ("kx11", self.kx11),
+            ("third_body_mean_anomaly_0", self.third_body_mean_anomaly_0),
+        ] {
+            values.insert(format!("{}.{}", prefix, name), *value);
+        }
+    }
 }
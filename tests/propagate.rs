@@ -39,3 +39,504 @@ fn propagate() -> sgp4::Result<()> {
     }
     Ok(())
 }
+
+#[test]
+fn sub_surface_perigee() -> sgp4::Result<()> {
+    // sub-orbital case (perigee -51 km) from the AFSPC verification set
+    let elements = sgp4::Elements::from_tle(
+        None,
+        "1 28872U 05037B   05333.02012661  .25992681  00000-0  24476-3 0  1534".as_bytes(),
+        "2 28872  96.4736 157.9986 0303955 244.0492 110.6523 16.46015938 10708".as_bytes(),
+    )?;
+    match sgp4::Constants::from_elements(&elements) {
+        Err(error) => assert_eq!(
+            format!("{}", error),
+            "the perigee is below the Earth's surface"
+        ),
+        Ok(_) => panic!("initialization should have returned an error"),
+    }
+    sgp4::Constants::from_elements_afspc_compatibility_mode(&elements)?;
+    Ok(())
+}
+
+#[test]
+fn kepler_solvers() -> sgp4::Result<()> {
+    // Molniya-like orbit with e₀ = 0.74
+    let constants = || {
+        sgp4::Constants::new(
+            &sgp4::WGS84,
+            sgp4::iau_epoch_to_sidereal_time,
+            6.5,
+            1.0e-4,
+            sgp4::Orbit::from_kozai_elements(
+                &sgp4::WGS84,
+                63.4 * (std::f64::consts::PI / 180.0),
+                279.0 * (std::f64::consts::PI / 180.0),
+                0.74,
+                270.0 * (std::f64::consts::PI / 180.0),
+                20.0 * (std::f64::consts::PI / 180.0),
+                2.0057 * (std::f64::consts::PI / 720.0),
+            )?,
+        )
+    };
+    let newton_raphson = constants()?;
+    let laguerre_conway = constants()?.with_kepler_solver(sgp4::KeplerSolver::LaguerreConway);
+    let mut newton_raphson_iterations = 0;
+    let mut laguerre_conway_iterations = 0;
+    for index in 0..144 {
+        let t = index as f64 * 10.0;
+        let (expected, newton_raphson_iterates) = newton_raphson.propagate_verbose(t)?;
+        let (prediction, laguerre_conway_iterates) = laguerre_conway.propagate_verbose(t)?;
+        assert!(laguerre_conway_iterates.len() <= newton_raphson_iterates.len());
+        newton_raphson_iterations += newton_raphson_iterates.len();
+        laguerre_conway_iterations += laguerre_conway_iterates.len();
+        for index in 0..3 {
+            assert!((expected.position[index] - prediction.position[index]).abs() < 1.0e-6);
+            assert!((expected.velocity[index] - prediction.velocity[index]).abs() < 1.0e-9);
+        }
+    }
+    assert!(laguerre_conway_iterations < newton_raphson_iterations);
+    Ok(())
+}
+
+#[test]
+fn propagate_checked() -> sgp4::Result<()> {
+    // near-zero eccentricity case from the AFSPC verification set
+    let constants = sgp4::Constants::from_elements(&sgp4::Elements::from_tle(
+        None,
+        "1 33335U 05008A   06176.46683397 -.00000205  00000-0  10000-3 0  2193".as_bytes(),
+        "2 33335   0.0019 286.9433 0000004  13.7918  55.6504  1.00270176  4897".as_bytes(),
+    )?)?;
+    let (prediction, warnings) = constants.propagate_checked(0.0)?;
+    assert_eq!(
+        warnings,
+        vec![sgp4::Warning::ClampedEccentricity {
+            eccentricity: 4.0e-7
+        }]
+    );
+    assert_eq!(prediction.position, constants.propagate(0.0)?.position);
+    let (_, t_max) = constants.recommended_time_range();
+    let (_, warnings) = constants.propagate_checked(t_max + 1.0)?;
+    assert!(warnings.contains(&sgp4::Warning::OutsideRecommendedTimeRange { t: t_max + 1.0 }));
+    Ok(())
+}
+
+#[test]
+fn critical_inclination() -> sgp4::Result<()> {
+    // cos²I = 1 / 5
+    let critical_inclination = (1.0 / 5.0_f64.sqrt()).acos();
+    let step = 0.01 * (std::f64::consts::PI / 180.0);
+
+    // Molniya-like deep space orbit and near earth orbit
+    for (eccentricity, kozai_mean_motion) in &[(0.74, 2.0057), (0.01, 14.5)] {
+        let constants = |inclination: f64| {
+            sgp4::Constants::new(
+                &sgp4::WGS84,
+                sgp4::iau_epoch_to_sidereal_time,
+                6.5,
+                1.0e-5,
+                sgp4::Orbit::from_kozai_elements(
+                    &sgp4::WGS84,
+                    inclination,
+                    279.0 * (std::f64::consts::PI / 180.0),
+                    *eccentricity,
+                    270.0 * (std::f64::consts::PI / 180.0),
+                    20.0 * (std::f64::consts::PI / 180.0),
+                    kozai_mean_motion * (std::f64::consts::PI / 720.0),
+                )?,
+            )
+        };
+        let mut sign_changes = 0;
+        let mut previous_rate = constants(critical_inclination - 50.0 * step)?
+            .secular_rates()
+            .argument_of_perigee_dot;
+        for index in -49..50 {
+            let inclination = critical_inclination + index as f64 * step;
+            let rate = constants(inclination)?
+                .secular_rates()
+                .argument_of_perigee_dot;
+            if rate.signum() != previous_rate.signum() {
+                sign_changes += 1;
+            }
+            previous_rate = rate;
+
+            // the predictions are smooth functions of the inclination
+            for t in &[0.0, 1440.0, 14400.0] {
+                let (before, before_warnings) =
+                    constants(inclination - step)?.propagate_checked(*t)?;
+                let (prediction, warnings) = constants(inclination)?.propagate_checked(*t)?;
+                let (after, after_warnings) =
+                    constants(inclination + step)?.propagate_checked(*t)?;
+                assert!(
+                    before_warnings.is_empty() && warnings.is_empty() && after_warnings.is_empty()
+                );
+                for component in 0..3 {
+                    let first_difference = after.position[component] - before.position[component];
+                    let second_difference = after.position[component]
+                        - 2.0 * prediction.position[component]
+                        + before.position[component];
+                    assert!(second_difference.abs() < 1.0e-2 * first_difference.abs().max(1.0));
+                }
+            }
+        }
+
+        // ω̇ vanishes once near the critical inclination
+        assert_eq!(sign_changes, 1);
+    }
+    Ok(())
+}
+
+#[test]
+fn circular_anomalies() -> sgp4::Result<()> {
+    // near-zero eccentricity case from the AFSPC verification set
+    let constants = sgp4::Constants::from_elements(&sgp4::Elements::from_tle(
+        None,
+        "1 33335U 05008A   06176.46683397 -.00000205  00000-0  10000-3 0  2193".as_bytes(),
+        "2 33335   0.0019 286.9433 0000004  13.7918  55.6504  1.00270176  4897".as_bytes(),
+    )?)?;
+    let anomalies = constants.anomalies(0.0)?;
+    assert!(anomalies.circular);
+    assert_eq!(anomalies.argument_of_perigee, 0.0);
+
+    // |ν - M| ≤ 2e + O(e²)
+    assert!(
+        (anomalies.true_anomaly - anomalies.mean_anomaly).abs() <= 2.0 * anomalies.eccentricity
+    );
+    Ok(())
+}
+
+// predictions at t = 1 day, recorded on x86_64 Linux (glibc) in debug and release builds
+// they are compared bit for bit on that platform, and elsewhere within tolerances that
+// catch numerical changes while allowing last-bit differences in the math library
+#[test]
+fn reference_vector() -> sgp4::Result<()> {
+    for (line1, line2, position, velocity) in &[
+        (
+            "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992",
+            "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008",
+            [
+                4859.187416544364_f64,
+                4630.996186605212,
+                -1076.5786401444489,
+            ],
+            [
+                -3.9979823487224837_f64,
+                2.836547584772732,
+                -5.885328791070976,
+            ],
+        ),
+        (
+            "1 08195U 75081A   06176.33215444  .00000099  00000-0  11873-3 0   813",
+            "2 08195  64.1586 279.0717 6877146 264.7651  20.2257  2.00491383225656",
+            [
+                2890.836125621838_f64,
+                -15446.430687571343,
+                948.8350195441081,
+            ],
+            [
+                2.6544076302480817_f64,
+                -2.9093317062926896,
+                4.486442176501099,
+            ],
+        ),
+    ] {
+        let prediction = sgp4::Constants::from_elements(&sgp4::Elements::from_tle(
+            None,
+            line1.as_bytes(),
+            line2.as_bytes(),
+        )?)?
+        .propagate(1440.0)?;
+        for component in 0..3 {
+            if cfg!(all(
+                target_arch = "x86_64",
+                target_os = "linux",
+                target_env = "gnu"
+            )) {
+                assert_eq!(
+                    prediction.position[component].to_bits(),
+                    position[component].to_bits()
+                );
+                assert_eq!(
+                    prediction.velocity[component].to_bits(),
+                    velocity[component].to_bits()
+                );
+            } else {
+                assert!((prediction.position[component] - position[component]).abs() < 1.0e-9);
+                assert!((prediction.velocity[component] - velocity[component]).abs() < 1.0e-12);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn deserialize_without_atmospheric_s() -> sgp4::Result<()> {
+    let elements = sgp4::Elements::from_tle(
+        None,
+        "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+        "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    )?;
+    let constants = sgp4::Constants::from_elements(&elements)?;
+
+    // propagators serialized before the atmospheric parameter override was stored
+    let mut value = serde_json::to_value(&constants)?;
+    value.as_object_mut().unwrap().remove("atmospheric_s");
+    let reloaded: sgp4::Constants = serde_json::from_value(value)?;
+    assert_eq!(
+        reloaded.atmospheric_parameters(),
+        constants.atmospheric_parameters()
+    );
+    assert_eq!(
+        reloaded.propagate(1440.0)?.position,
+        constants.propagate(1440.0)?.position
+    );
+
+    let s = 1.0 + 90.0 / sgp4::WGS84.ae;
+    let experimental_constants = constants.with_atmospheric_s(s)?;
+    let reloaded: sgp4::Constants =
+        serde_json::from_str(&serde_json::to_string(&experimental_constants)?)?;
+    assert_eq!(reloaded.atmospheric_parameters().s, s);
+    Ok(())
+}
+
+#[test]
+fn unbounded_windows() -> sgp4::Result<()> {
+    let constants = sgp4::Constants::from_elements(&sgp4::Elements::from_tle(
+        None,
+        "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+        "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    )?)?;
+    let too_long = (sgp4::MAX_SAMPLES + 1) as f64;
+    assert!(constants.terminator_crossings(0.0, f64::INFINITY).is_err());
+    assert!(constants.terminator_crossings(0.0, too_long).is_err());
+    assert!(sgp4::screen_conjunctions(&constants, &[], 0.0..f64::INFINITY, 10.0).is_err());
+    assert!(sgp4::screen_conjunctions(&constants, &[], 0.0..too_long, 10.0).is_err());
+    assert!(constants.altitude_extrema(0.0, f64::INFINITY).is_err());
+    assert!(constants.altitude_extrema(0.0, too_long).is_err());
+    assert!(constants.orbit_count(f64::NEG_INFINITY).is_err());
+    assert!(constants.orbit_count(f64::NAN).is_err());
+    let observer = sgp4::Geodetic {
+        latitude: 0.0,
+        longitude: 0.0,
+        altitude: 0.0,
+    };
+    assert!(constants.passes(observer, 0.0, f64::INFINITY, 0.0).is_err());
+    assert!(constants
+        .visibility_windows(observer, 0.0, too_long, 0.0)
+        .is_err());
+    assert!(constants
+        .eclipse_fraction(0.0, f64::INFINITY, true)
+        .is_err());
+    assert!(constants
+        .eclipse_fraction(0.0, 0.1 * too_long, true)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn write_oem() -> sgp4::Result<()> {
+    let constants = sgp4::Constants::from_elements(&sgp4::Elements::from_tle(
+        Some("ISS (ZARYA)".to_owned()),
+        "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+        "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    )?)?;
+    let metadata = sgp4::OemMetadata {
+        creation_date: chrono::NaiveDate::from_ymd_opt(2020, 7, 13)
+            .and_then(|date| date.and_hms_opt(8, 30, 0))
+            .unwrap(),
+        originator: "EXAMPLE".to_owned(),
+        object_name: "ISS (ZARYA)".to_owned(),
+        object_id: "1998-067A".to_owned(),
+        frame: sgp4::OemFrame::Teme,
+    };
+    let mut oem = Vec::new();
+    constants.write_oem(&mut oem, 0.0, 10.5, 1.0, &metadata)?;
+    let oem = String::from_utf8(oem).unwrap();
+    let lines = oem.lines().collect::<Vec<&str>>();
+    assert_eq!(
+        &lines[..14],
+        &[
+            "CCSDS_OEM_VERS = 2.0",
+            "CREATION_DATE = 2020-07-13T08:30:00",
+            "ORIGINATOR = EXAMPLE",
+            "",
+            "META_START",
+            "OBJECT_NAME = ISS (ZARYA)",
+            "OBJECT_ID = 1998-067A",
+            "CENTER_NAME = EARTH",
+            "REF_FRAME = TEME",
+            "TIME_SYSTEM = UTC",
+            "START_TIME = 2020-07-12T21:16:01.000415",
+            "STOP_TIME = 2020-07-12T21:26:01.000415",
+            "META_STOP",
+            "",
+        ]
+    );
+    assert_eq!(lines.len(), 14 + 11);
+    let prediction = constants.propagate(0.0)?;
+    assert_eq!(
+        lines[14],
+        format!(
+            "2020-07-12T21:16:01.000415 {:.6} {:.6} {:.6} {:.9} {:.9} {:.9}",
+            prediction.position[0],
+            prediction.position[1],
+            prediction.position[2],
+            prediction.velocity[0],
+            prediction.velocity[1],
+            prediction.velocity[2],
+        )
+    );
+    let fields = lines[24].split(' ').collect::<Vec<&str>>();
+    assert_eq!(fields.len(), 7);
+    assert_eq!(fields[0], "2020-07-12T21:26:01.000415");
+
+    assert!(constants
+        .write_oem(Vec::new(), 0.0, f64::INFINITY, 1.0, &metadata)
+        .is_err());
+    assert!(constants
+        .write_oem(Vec::new(), 0.0, sgp4::MAX_SAMPLES as f64, 1.0, &metadata)
+        .is_err());
+    Ok(())
+}
+
+#[test]
+fn look_angles() -> sgp4::Result<()> {
+    let constants = sgp4::Constants::from_elements(&sgp4::Elements::from_tle(
+        None,
+        "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+        "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    )?)?;
+
+    // an observer on the Earth's surface below the satellite sees it near the zenith
+    let (_, prediction) = constants.propagate_frames(30.0)?;
+    let radius = (prediction.position[0].powi(2)
+        + prediction.position[1].powi(2)
+        + prediction.position[2].powi(2))
+    .sqrt();
+    let scale = (sgp4::WGS84.ae - 10.0) / radius;
+    let look_angles = constants.look_angles(
+        sgp4::EcefObserver([
+            prediction.position[0] * scale,
+            prediction.position[1] * scale,
+            prediction.position[2] * scale,
+        ]),
+        30.0,
+    )?;
+    assert!(look_angles.elevation > 89.0 * (std::f64::consts::PI / 180.0));
+    assert!((look_angles.range - radius * (1.0 - scale)).abs() < 1.0e-6);
+    assert!(look_angles.azimuth_rate.is_finite());
+
+    // Geodetic and EcefObserver observers at the same place give the same look angles
+    let observer = sgp4::Geodetic {
+        latitude: 48.8566 * (std::f64::consts::PI / 180.0),
+        longitude: 2.3522 * (std::f64::consts::PI / 180.0),
+        altitude: 0.035,
+    };
+    let ecef_observer: sgp4::EcefObserver = observer.into();
+    for t in [0.0, 100.0, 1000.0].iter() {
+        let geodetic_angles = constants.look_angles(observer, *t)?;
+        let ecef_angles = constants.look_angles(ecef_observer, *t)?;
+        assert!((geodetic_angles.azimuth - ecef_angles.azimuth).abs() < 1.0e-9);
+        assert!((geodetic_angles.elevation - ecef_angles.elevation).abs() < 1.0e-9);
+        assert!((geodetic_angles.range - ecef_angles.range).abs() < 1.0e-6);
+    }
+
+    // the angular rates match central differences over ±1 s
+    let t = 1000.0;
+    let before = constants.look_angles(observer, t - 1.0 / 60.0)?;
+    let look_angles = constants.look_angles(observer, t)?;
+    let after = constants.look_angles(observer, t + 1.0 / 60.0)?;
+    assert!(
+        (look_angles.elevation_rate - (after.elevation - before.elevation) / 2.0).abs() < 1.0e-7
+    );
+    assert!((look_angles.azimuth_rate - (after.azimuth - before.azimuth) / 2.0).abs() < 1.0e-7);
+    Ok(())
+}
+
+#[test]
+fn passes() -> sgp4::Result<()> {
+    let constants = sgp4::Constants::from_elements(&sgp4::Elements::from_tle(
+        None,
+        "1 25544U 98067A   20194.88612269 -.00002218  00000-0 -31515-4 0  9992".as_bytes(),
+        "2 25544  51.6461 221.2784 0001413  89.1723 280.4612 15.49507896236008".as_bytes(),
+    )?)?;
+    let observer = sgp4::Geodetic {
+        latitude: 48.8566 * (std::f64::consts::PI / 180.0),
+        longitude: 2.3522 * (std::f64::consts::PI / 180.0),
+        altitude: 0.035,
+    };
+    let min_elevation = 10.0 * (std::f64::consts::PI / 180.0);
+    let passes = constants.passes(observer, 0.0, 60.0 * 24.0, min_elevation)?;
+    assert!(!passes.is_empty());
+    assert_eq!(
+        constants.visibility_windows(observer, 0.0, 60.0 * 24.0, min_elevation)?,
+        passes
+            .iter()
+            .map(|pass| (pass.rise, pass.set))
+            .collect::<Vec<(f64, f64)>>()
+    );
+    for (index, pass) in passes.iter().enumerate() {
+        assert!(pass.rise < pass.culmination && pass.culmination < pass.set);
+        assert!(pass.max_elevation > min_elevation);
+        if index > 0 {
+            assert!(passes[index - 1].set < pass.rise);
+        }
+
+        // the elevation crosses the mask upwards at rise and downwards at set
+        let rise = constants.look_angles(observer, pass.rise)?;
+        let set = constants.look_angles(observer, pass.set)?;
+        assert!((rise.elevation - min_elevation).abs() < 1.0e-6);
+        assert!((set.elevation - min_elevation).abs() < 1.0e-6);
+        assert!(rise.elevation_rate > 0.0);
+        assert!(set.elevation_rate < 0.0);
+        assert!(
+            constants
+                .look_angles(observer, pass.rise - 1.0e-3)?
+                .elevation
+                < min_elevation
+        );
+        assert!(
+            constants
+                .look_angles(observer, pass.rise + 1.0e-3)?
+                .elevation
+                > min_elevation
+        );
+        assert!(
+            constants
+                .look_angles(observer, pass.set - 1.0e-3)?
+                .elevation
+                > min_elevation
+        );
+        assert!(
+            constants
+                .look_angles(observer, pass.set + 1.0e-3)?
+                .elevation
+                < min_elevation
+        );
+
+        // the culmination is a maximum of the elevation, close to the time of closest approach
+        let culmination = constants.look_angles(observer, pass.culmination)?;
+        assert!((culmination.elevation - pass.max_elevation).abs() < 1.0e-12);
+        assert!(culmination.elevation_rate.abs() < 1.0e-5);
+        for dt in [-0.5, 0.5].iter() {
+            assert!(
+                constants
+                    .look_angles(observer, pass.culmination + dt)?
+                    .elevation
+                    < pass.max_elevation
+            );
+        }
+
+        // the range decreases before the closest approach and increases after it
+        let range_rate = |t: f64| -> sgp4::Result<f64> {
+            Ok((constants.look_angles(observer, t + 1.0e-3)?.range
+                - constants.look_angles(observer, t - 1.0e-3)?.range)
+                / 2.0e-3)
+        };
+        assert!(range_rate(pass.rise)? < 0.0);
+        assert!(range_rate(pass.set)? > 0.0);
+        assert!(range_rate(pass.culmination - 1.0)? < 0.0);
+        assert!(range_rate(pass.culmination + 1.0)? > 0.0);
+    }
+    Ok(())
+}